@@ -1,19 +1,66 @@
 use yew::prelude::*;
 use yew_ethereum_provider::{
-    chain, AccountLabel, ConnectButton, EthereumContextProvider, SwitchNetworkButton,
+    chain, use_wallet_info, AccountLabel, ConnectButton, EthereumContextProvider,
+    SwitchNetworkButton, UseEthereumHandle,
 };
 
+#[function_component]
+fn StatusBar() -> Html {
+    let ethereum = use_context::<Option<UseEthereumHandle>>().flatten();
+    let wallet = use_wallet_info(ethereum);
+
+    html! {
+        <div class="status-bar">
+            if let Some(wallet) = wallet {
+                if let Some(icon) = wallet.icon {
+                    <img src={icon} height="16" width="16" alt={wallet.name.clone()} />
+                }
+                {wallet.name}
+            }
+            <AccountLabel show_balance=true />
+        </div>
+    }
+}
+
+/// logs every request and its outcome to the console
+#[function_component]
+fn RequestLogger() -> Html {
+    let ethereum = use_context::<Option<UseEthereumHandle>>().flatten();
+    use_effect_with_deps(
+        |ethereum| {
+            if let Some(ethereum) = ethereum {
+                ethereum.add_request_middleware(|method, params| {
+                    log::info!("-> {} {:?}", method, params);
+                    None
+                });
+                ethereum.add_response_middleware(|method, response| {
+                    log::info!("<- {} {:?}", method, response);
+                });
+            }
+            let ethereum = ethereum.clone();
+            move || {
+                if let Some(ethereum) = ethereum {
+                    ethereum.clear_middleware();
+                }
+            }
+        },
+        ethereum,
+    );
+    html! {}
+}
+
 #[function_component]
 pub fn App() -> Html {
     html! {
         <div>
             <EthereumContextProvider>
+                <RequestLogger />
                 <ConnectButton>
                     <button>{"Connect"}</button>
                 </ConnectButton>
                 <SwitchNetworkButton chain={chain::ethereum()}/>
                 <SwitchNetworkButton chain={chain::avalanche_testnet()}/>
-                <AccountLabel />
+                <StatusBar />
             </EthereumContextProvider>
         </div>
     }
@@ -0,0 +1,619 @@
+//! Minimal helpers for encoding contract calls and decoding their results, for use with
+//! `UseEthereumHandle::call_decoded` when pulling in a full ABI library isn't worth it
+use std::fmt;
+use web3::{
+    signing::keccak256,
+    types::{H160, U256},
+};
+
+/// size in bytes of a single ABI word
+pub const WORD: usize = 32;
+
+/// Errors produced while decoding ABI encoded return data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// the data is shorter than the value being decoded requires
+    TooShort { expected: usize, actual: usize },
+    /// the data has the right length but not a valid value for the type
+    InvalidValue(String),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::TooShort { expected, actual } => {
+                write!(f, "expected at least {} bytes, got {}", expected, actual)
+            }
+            DecodeError::InvalidValue(reason) => write!(f, "invalid value: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// 4-byte function selector of a signature such as `"balanceOf(address)"`
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// concatenate a selector and already encoded arguments into calldata
+pub fn encode_call(selector: [u8; 4], args: &[[u8; WORD]]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(4 + args.len() * WORD);
+    data.extend_from_slice(&selector);
+    for arg in args {
+        data.extend_from_slice(arg);
+    }
+    data
+}
+
+/// encode an address left-padded to a full word
+pub fn encode_address(address: &H160) -> [u8; WORD] {
+    let mut word = [0u8; WORD];
+    word[12..].copy_from_slice(address.as_bytes());
+    word
+}
+
+/// encode an unsigned integer as a big-endian word
+pub fn encode_uint(value: U256) -> [u8; WORD] {
+    let mut word = [0u8; WORD];
+    value.to_big_endian(&mut word);
+    word
+}
+
+/// encode a bool as a word holding 0 or 1
+pub fn encode_bool(value: bool) -> [u8; WORD] {
+    let mut word = [0u8; WORD];
+    word[WORD - 1] = value as u8;
+    word
+}
+
+/// the `index`th word of `data`
+pub fn word(data: &[u8], index: usize) -> Result<&[u8], DecodeError> {
+    let end = (index + 1) * WORD;
+    data.get(index * WORD..end).ok_or(DecodeError::TooShort {
+        expected: end,
+        actual: data.len(),
+    })
+}
+
+/// decode the first word of `data` as an unsigned integer
+pub fn decode_uint(data: &[u8]) -> Result<U256, DecodeError> {
+    word(data, 0).map(U256::from_big_endian)
+}
+
+/// decode the first word of `data` as a `uint8`
+pub fn decode_u8(data: &[u8]) -> Result<u8, DecodeError> {
+    let value = decode_uint(data)?;
+    if value > U256::from(u8::MAX) {
+        return Err(DecodeError::InvalidValue(format!(
+            "{} does not fit in a uint8",
+            value
+        )));
+    }
+    Ok(value.low_u32() as u8)
+}
+
+/// decode the first word of `data` as an address, rejecting dirty upper bytes
+pub fn decode_address(data: &[u8]) -> Result<H160, DecodeError> {
+    let word = word(data, 0)?;
+    if word[..12].iter().any(|byte| *byte != 0) {
+        return Err(DecodeError::InvalidValue(String::from(
+            "address has non-zero padding",
+        )));
+    }
+    Ok(H160::from_slice(&word[12..]))
+}
+
+/// decode the first word of `data` as a bool
+pub fn decode_bool(data: &[u8]) -> Result<bool, DecodeError> {
+    match decode_uint(data)? {
+        value if value.is_zero() => Ok(false),
+        value if value == U256::one() => Ok(true),
+        value => Err(DecodeError::InvalidValue(format!(
+            "{} is not a bool",
+            value
+        ))),
+    }
+}
+
+/// decode the first word of `data` as an offset or length, rejecting values that can't index
+/// memory
+pub fn decode_usize(data: &[u8]) -> Result<usize, DecodeError> {
+    let value = decode_uint(data)?;
+    if value > U256::from(u32::MAX) {
+        return Err(DecodeError::InvalidValue(format!(
+            "{} is too large for an offset or length",
+            value
+        )));
+    }
+    Ok(value.as_usize())
+}
+
+/// `data` from `offset` on
+pub fn tail(data: &[u8], offset: usize) -> Result<&[u8], DecodeError> {
+    data.get(offset..).ok_or(DecodeError::TooShort {
+        expected: offset,
+        actual: data.len(),
+    })
+}
+
+/// decode the dynamic `bytes` value whose length word is at `offset`
+pub fn decode_bytes_at(data: &[u8], offset: usize) -> Result<Vec<u8>, DecodeError> {
+    let tail = tail(data, offset)?;
+    let length = decode_usize(tail)?;
+    let too_short = DecodeError::TooShort {
+        expected: offset.saturating_add(WORD).saturating_add(length),
+        actual: data.len(),
+    };
+    let end = WORD.checked_add(length).ok_or_else(|| too_short.clone())?;
+    tail.get(WORD..end).map(<[u8]>::to_vec).ok_or(too_short)
+}
+
+/// decode `data` holding a single dynamic `bytes` value: the offset of the value, then at that
+/// offset its length followed by the contents
+pub fn decode_bytes(data: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    decode_bytes_at(data, decode_usize(data)?)
+}
+
+/// decode `data` holding a single dynamic `string` value
+pub fn decode_string(data: &[u8]) -> Result<String, DecodeError> {
+    String::from_utf8(decode_bytes(data)?)
+        .map_err(|err| DecodeError::InvalidValue(format!("string is not utf-8: {}", err)))
+}
+
+/// selector of the `Error(string)` payload of `require` and `revert("...")`
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// selector of the `Panic(uint256)` payload of failed asserts, overflows and the like
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// the reason in the revert data of a failed call: the message of an `Error(string)` or a
+/// description of a `Panic(uint256)` code. `None` for custom errors and empty reverts
+pub fn decode_revert_reason(data: &[u8]) -> Option<String> {
+    let (selector, args) = (data.get(..4)?, data.get(4..)?);
+    if selector == ERROR_SELECTOR {
+        decode_string(args).ok()
+    } else if selector == PANIC_SELECTOR {
+        let code = decode_uint(args).ok()?;
+        Some(panic_description(code))
+    } else {
+        None
+    }
+}
+
+/// what the solidity compiler inserted the `Panic(code)` for
+fn panic_description(code: U256) -> String {
+    let description = match code.low_u64() {
+        _ if code > U256::from(u64::MAX) => None,
+        0x00 => Some("generic compiler panic"),
+        0x01 => Some("assertion failed"),
+        0x11 => Some("arithmetic overflow or underflow"),
+        0x12 => Some("division or modulo by zero"),
+        0x21 => Some("invalid enum value"),
+        0x22 => Some("invalid storage byte array encoding"),
+        0x31 => Some("pop on an empty array"),
+        0x32 => Some("array index out of bounds"),
+        0x41 => Some("out of memory"),
+        0x51 => Some("call to an uninitialized function"),
+        _ => None,
+    };
+    match description {
+        Some(description) => format!("panic: {}", description),
+        None => format!("panic: code {:#x}", code),
+    }
+}
+
+/// the tail encoding of a dynamic `bytes` value: its length followed by the contents padded to
+/// whole words
+pub fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    let padding = (WORD - bytes.len() % WORD) % WORD;
+    let mut encoded = encode_uint(U256::from(bytes.len())).to_vec();
+    encoded.extend_from_slice(bytes);
+    encoded.resize(WORD + bytes.len() + padding, 0);
+    encoded
+}
+
+/// An ABI type, for decoding values with `decode`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamType {
+    Address,
+    Uint,
+    Bool,
+    Bytes,
+    String,
+    /// a dynamic `T[]`
+    Array(Box<ParamType>),
+    Tuple(Vec<ParamType>),
+}
+
+impl ParamType {
+    /// whether values of the type are encoded out of line, behind an offset
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            ParamType::Address | ParamType::Uint | ParamType::Bool => false,
+            ParamType::Bytes | ParamType::String | ParamType::Array(_) => true,
+            ParamType::Tuple(types) => types.iter().any(ParamType::is_dynamic),
+        }
+    }
+
+    /// bytes taken in the head of the enclosing sequence
+    fn head_size(&self) -> usize {
+        match self {
+            ParamType::Tuple(types) if !self.is_dynamic() => {
+                types.iter().map(ParamType::head_size).sum()
+            }
+            _ => WORD,
+        }
+    }
+}
+
+/// A value of one of the `ParamType`s
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Address(H160),
+    Uint(U256),
+    Bool(bool),
+    Bytes(Vec<u8>),
+    String(String),
+    Array(Vec<Token>),
+    Tuple(Vec<Token>),
+}
+
+impl Token {
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            Token::Address(_) | Token::Uint(_) | Token::Bool(_) => false,
+            Token::Bytes(_) | Token::String(_) | Token::Array(_) => true,
+            Token::Tuple(tokens) => tokens.iter().any(Token::is_dynamic),
+        }
+    }
+
+    pub fn into_address(self) -> Option<H160> {
+        match self {
+            Token::Address(address) => Some(address),
+            _ => None,
+        }
+    }
+
+    pub fn into_uint(self) -> Option<U256> {
+        match self {
+            Token::Uint(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn into_bool(self) -> Option<bool> {
+        match self {
+            Token::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn into_bytes(self) -> Option<Vec<u8>> {
+        match self {
+            Token::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    pub fn into_string(self) -> Option<String> {
+        match self {
+            Token::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// the elements of an array or the fields of a tuple
+    pub fn into_tokens(self) -> Option<Vec<Token>> {
+        match self {
+            Token::Array(tokens) | Token::Tuple(tokens) => Some(tokens),
+            _ => None,
+        }
+    }
+}
+
+/// encode `tokens` as a sequence, eg. the arguments of a call following its selector
+pub fn encode(tokens: &[Token]) -> Vec<u8> {
+    let encoded: Vec<(bool, Vec<u8>)> = tokens
+        .iter()
+        .map(|token| (token.is_dynamic(), encode_token(token)))
+        .collect();
+    let head_size: usize = encoded
+        .iter()
+        .map(|(dynamic, data)| if *dynamic { WORD } else { data.len() })
+        .sum();
+
+    let mut head = Vec::with_capacity(head_size);
+    let mut tail = Vec::new();
+    for (dynamic, data) in encoded {
+        if dynamic {
+            head.extend_from_slice(&encode_uint(U256::from(head_size + tail.len())));
+            tail.extend_from_slice(&data);
+        } else {
+            head.extend_from_slice(&data);
+        }
+    }
+    head.extend_from_slice(&tail);
+    head
+}
+
+fn encode_token(token: &Token) -> Vec<u8> {
+    match token {
+        Token::Address(address) => encode_address(address).to_vec(),
+        Token::Uint(value) => encode_uint(*value).to_vec(),
+        Token::Bool(value) => encode_bool(*value).to_vec(),
+        Token::Bytes(bytes) => encode_bytes(bytes),
+        Token::String(string) => encode_bytes(string.as_bytes()),
+        Token::Array(tokens) => {
+            let mut data = encode_uint(U256::from(tokens.len())).to_vec();
+            data.extend_from_slice(&encode(tokens));
+            data
+        }
+        Token::Tuple(tokens) => encode(tokens),
+    }
+}
+
+/// decode `data` holding a sequence of values of `types`, eg. the return data of a call
+pub fn decode(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, DecodeError> {
+    decode_sequence(types.iter(), data)
+}
+
+fn decode_sequence<'a>(
+    types: impl Iterator<Item = &'a ParamType>,
+    data: &[u8],
+) -> Result<Vec<Token>, DecodeError> {
+    let mut head = 0;
+    types
+        .map(|kind| {
+            let at = if kind.is_dynamic() {
+                decode_usize(tail(data, head)?)?
+            } else {
+                head
+            };
+            head += kind.head_size();
+            decode_param(kind, tail(data, at)?)
+        })
+        .collect()
+}
+
+fn decode_param(kind: &ParamType, data: &[u8]) -> Result<Token, DecodeError> {
+    match kind {
+        ParamType::Address => decode_address(data).map(Token::Address),
+        ParamType::Uint => decode_uint(data).map(Token::Uint),
+        ParamType::Bool => decode_bool(data).map(Token::Bool),
+        ParamType::Bytes => decode_bytes_at(data, 0).map(Token::Bytes),
+        ParamType::String => String::from_utf8(decode_bytes_at(data, 0)?)
+            .map(Token::String)
+            .map_err(|err| DecodeError::InvalidValue(format!("string is not utf-8: {}", err))),
+        ParamType::Array(inner) => {
+            let length = decode_usize(data)?;
+            let elements = tail(data, WORD)?;
+            // every element takes at least a word, so a length the data can't hold is rejected
+            // before decoding anything
+            if length > elements.len() / WORD {
+                return Err(DecodeError::TooShort {
+                    expected: WORD.saturating_add(length.saturating_mul(WORD)),
+                    actual: data.len(),
+                });
+            }
+            decode_sequence((0..length).map(|_| &**inner), elements).map(Token::Array)
+        }
+        ParamType::Tuple(types) => decode(types, data).map(Token::Tuple),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    fn address(byte: u8) -> H160 {
+        H160::repeat_byte(byte)
+    }
+
+    #[test]
+    fn static_tuple_is_encoded_inline() {
+        let tuple = Token::Tuple(vec![
+            Token::Address(address(0x11)),
+            Token::Uint(U256::from(7)),
+            Token::Bool(true),
+        ]);
+        let data = hex(
+            "0000000000000000000000001111111111111111111111111111111111111111
+             0000000000000000000000000000000000000000000000000000000000000007
+             0000000000000000000000000000000000000000000000000000000000000001",
+        );
+        assert_eq!(encode(&[tuple.clone()]), data);
+
+        let types = [ParamType::Tuple(vec![
+            ParamType::Address,
+            ParamType::Uint,
+            ParamType::Bool,
+        ])];
+        assert_eq!(decode(&types, &data), Ok(vec![tuple]));
+    }
+
+    #[test]
+    fn dynamic_tuple_is_behind_an_offset() {
+        let tuple = Token::Tuple(vec![
+            Token::Uint(U256::from(1)),
+            Token::String(String::from("hi")),
+        ]);
+        let data = hex(
+            "0000000000000000000000000000000000000000000000000000000000000020
+             0000000000000000000000000000000000000000000000000000000000000001
+             0000000000000000000000000000000000000000000000000000000000000040
+             0000000000000000000000000000000000000000000000000000000000000002
+             6869000000000000000000000000000000000000000000000000000000000000",
+        );
+        assert_eq!(encode(&[tuple.clone()]), data);
+
+        let types = [ParamType::Tuple(vec![ParamType::Uint, ParamType::String])];
+        assert_eq!(decode(&types, &data), Ok(vec![tuple]));
+    }
+
+    #[test]
+    fn bytes_are_padded_to_whole_words() {
+        assert_eq!(encode_bytes(&[]), hex(&"00".repeat(32)));
+        assert_eq!(
+            encode_bytes(b"hello"),
+            hex(
+                "0000000000000000000000000000000000000000000000000000000000000005
+                 68656c6c6f000000000000000000000000000000000000000000000000000000"
+            )
+        );
+        // a full word needs no padding
+        assert_eq!(encode_bytes(&[0xff; 32]).len(), 2 * WORD);
+        assert_eq!(encode_bytes(&[0xff; 33]).len(), 3 * WORD);
+    }
+
+    #[test]
+    fn string_round_trips_through_offset_and_length() {
+        let data = hex(
+            "0000000000000000000000000000000000000000000000000000000000000020
+             0000000000000000000000000000000000000000000000000000000000000005
+             68656c6c6f000000000000000000000000000000000000000000000000000000",
+        );
+        assert_eq!(decode_string(&data), Ok(String::from("hello")));
+        assert_eq!(encode(&[Token::String(String::from("hello"))]), data);
+    }
+
+    #[test]
+    fn nested_arrays() {
+        let value = Token::Array(vec![
+            Token::Array(vec![Token::Uint(U256::from(1)), Token::Uint(U256::from(2))]),
+            Token::Array(vec![Token::Uint(U256::from(3))]),
+        ]);
+        let data = hex(
+            "0000000000000000000000000000000000000000000000000000000000000020
+             0000000000000000000000000000000000000000000000000000000000000002
+             0000000000000000000000000000000000000000000000000000000000000040
+             00000000000000000000000000000000000000000000000000000000000000a0
+             0000000000000000000000000000000000000000000000000000000000000002
+             0000000000000000000000000000000000000000000000000000000000000001
+             0000000000000000000000000000000000000000000000000000000000000002
+             0000000000000000000000000000000000000000000000000000000000000001
+             0000000000000000000000000000000000000000000000000000000000000003",
+        );
+        assert_eq!(encode(&[value.clone()]), data);
+
+        let types = [ParamType::Array(Box::new(ParamType::Array(Box::new(
+            ParamType::Uint,
+        ))))];
+        assert_eq!(decode(&types, &data), Ok(vec![value]));
+    }
+
+    #[test]
+    fn out_of_range_offset_is_rejected() {
+        let data = hex("0000000000000000000000000000000000000000000000000000000000001000");
+        assert_eq!(
+            decode(&[ParamType::String], &data),
+            Err(DecodeError::TooShort {
+                expected: 0x1000,
+                actual: 32
+            })
+        );
+    }
+
+    #[test]
+    fn oversized_offset_is_rejected() {
+        let data = hex("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+        assert!(matches!(
+            decode_bytes(&data),
+            Err(DecodeError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn truncated_data_is_rejected() {
+        assert_eq!(
+            decode_uint(&[0u8; 31]),
+            Err(DecodeError::TooShort {
+                expected: 32,
+                actual: 31
+            })
+        );
+
+        // a 64 byte value of which only one word is present
+        let data = hex(
+            "0000000000000000000000000000000000000000000000000000000000000020
+             0000000000000000000000000000000000000000000000000000000000000040
+             6869000000000000000000000000000000000000000000000000000000000000",
+        );
+        assert_eq!(
+            decode_bytes(&data),
+            Err(DecodeError::TooShort {
+                expected: 128,
+                actual: 96
+            })
+        );
+
+        // an array claiming 10 elements but holding one
+        let data = hex(
+            "0000000000000000000000000000000000000000000000000000000000000020
+             000000000000000000000000000000000000000000000000000000000000000a
+             0000000000000000000000000000000000000000000000000000000000000005",
+        );
+        assert_eq!(
+            decode(&[ParamType::Array(Box::new(ParamType::Uint))], &data),
+            Err(DecodeError::TooShort {
+                expected: 352,
+                actual: 64
+            })
+        );
+    }
+
+    #[test]
+    fn dirty_address_padding_is_rejected() {
+        let data = hex("0000000000000000000000011111111111111111111111111111111111111111");
+        assert!(matches!(
+            decode_address(&data),
+            Err(DecodeError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn revert_selectors() {
+        assert_eq!(selector("Error(string)"), ERROR_SELECTOR);
+        assert_eq!(selector("Panic(uint256)"), PANIC_SELECTOR);
+        assert_eq!(selector("balanceOf(address)"), [0x70, 0xa0, 0x82, 0x31]);
+    }
+
+    #[test]
+    fn error_string_revert_reason() {
+        let data = hex("08c379a0
+             0000000000000000000000000000000000000000000000000000000000000020
+             000000000000000000000000000000000000000000000000000000000000001a
+             4e6f7420656e6f7567682045746865722070726f76696465642e000000000000");
+        assert_eq!(
+            decode_revert_reason(&data),
+            Some(String::from("Not enough Ether provided."))
+        );
+    }
+
+    #[test]
+    fn panic_revert_reason() {
+        let panic = |code: &str| hex(&format!("4e487b71{:0>64}", code));
+        assert_eq!(
+            decode_revert_reason(&panic("11")),
+            Some(String::from("panic: arithmetic overflow or underflow"))
+        );
+        assert_eq!(
+            decode_revert_reason(&panic("32")),
+            Some(String::from("panic: array index out of bounds"))
+        );
+        assert_eq!(
+            decode_revert_reason(&panic("99")),
+            Some(String::from("panic: code 0x99"))
+        );
+    }
+
+    #[test]
+    fn other_reverts_have_no_reason() {
+        // a custom error and data shorter than a selector
+        assert_eq!(decode_revert_reason(&hex("f4d678b8")), None);
+        assert_eq!(decode_revert_reason(&hex("08c379")), None);
+        // an `Error(string)` with a truncated payload
+        assert_eq!(decode_revert_reason(&hex("08c379a0")), None);
+    }
+}
@@ -0,0 +1,137 @@
+//! Formatting of addresses for display
+use web3::{signing::keccak256, types::H160};
+
+/// How `format_address` renders an address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressStyle {
+    /// all 40 hex digits in lowercase, eg. `0xd8da6bf26964af9d7eed9e03e53415d37aa96045`
+    Full,
+    /// all 40 hex digits with the EIP-55 mixed-case checksum
+    Checksummed,
+    /// the first `lead` and last `trail` hex digits around an ellipsis, eg. `0xd8dA…6045`
+    Short { lead: usize, trail: usize },
+    /// the primary ENS name if it has been looked up, otherwise `Short { lead: 4, trail: 4 }`
+    EnsOrShort,
+}
+
+/// EIP-55: `0x`-prefixed hex with each letter uppercased where the matching nibble of the
+/// keccak256 hash of the lowercase hex is 8 or more
+pub fn to_checksum(address: &H160) -> String {
+    let hex = format!("{:x}", address);
+    let hash = keccak256(hex.as_bytes());
+    let digits: String = hex
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{}", digits)
+}
+
+/// render `address` in `style`. `ens_name` is the primary name used by `EnsOrShort`
+pub fn format_address(address: &H160, style: AddressStyle, ens_name: Option<&str>) -> String {
+    match style {
+        AddressStyle::Full => format!("{:?}", address),
+        AddressStyle::Checksummed => to_checksum(address),
+        AddressStyle::Short { lead, trail } => {
+            let checksummed = to_checksum(address);
+            let digits = &checksummed[2..];
+            if lead + trail >= digits.len() {
+                return checksummed;
+            }
+            format!("0x{}…{}", &digits[..lead], &digits[digits.len() - trail..])
+        }
+        AddressStyle::EnsOrShort => match ens_name {
+            Some(name) => name.to_string(),
+            None => format_address(address, AddressStyle::Short { lead: 4, trail: 4 }, None),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    fn address(hex_address: &str) -> H160 {
+        H160::from_slice(&hex(hex_address))
+    }
+
+    #[test]
+    fn eip55_reference_addresses() {
+        for checksummed in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            assert_eq!(to_checksum(&address(checksummed)), checksummed);
+        }
+    }
+
+    #[test]
+    fn checksum_ignores_input_case() {
+        let lower = address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed");
+        assert_eq!(
+            to_checksum(&lower),
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+        );
+    }
+
+    const VITALIK: &str = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
+
+    #[test]
+    fn full_and_checksummed() {
+        let vitalik = address(VITALIK);
+        assert_eq!(
+            format_address(&vitalik, AddressStyle::Full, None),
+            "0xd8da6bf26964af9d7eed9e03e53415d37aa96045"
+        );
+        assert_eq!(
+            format_address(&vitalik, AddressStyle::Checksummed, None),
+            VITALIK
+        );
+    }
+
+    #[test]
+    fn short_widths() {
+        let vitalik = address(VITALIK);
+        let short =
+            |lead, trail| format_address(&vitalik, AddressStyle::Short { lead, trail }, None);
+        assert_eq!(short(4, 4), "0xd8dA…6045");
+        assert_eq!(short(6, 4), "0xd8dA6B…6045");
+        assert_eq!(short(2, 6), "0xd8…A96045");
+        assert_eq!(short(0, 4), "0x…6045");
+        // nothing left to elide
+        assert_eq!(short(20, 20), VITALIK);
+        assert_eq!(short(30, 30), VITALIK);
+    }
+
+    #[test]
+    fn ens_name_overrides_the_address() {
+        let vitalik = address(VITALIK);
+        assert_eq!(
+            format_address(&vitalik, AddressStyle::EnsOrShort, Some("vitalik.eth")),
+            "vitalik.eth"
+        );
+        assert_eq!(
+            format_address(&vitalik, AddressStyle::EnsOrShort, None),
+            "0xd8dA…6045"
+        );
+        // only `EnsOrShort` uses the name
+        assert_eq!(
+            format_address(
+                &vitalik,
+                AddressStyle::Short { lead: 4, trail: 4 },
+                Some("vitalik.eth")
+            ),
+            "0xd8dA…6045"
+        );
+    }
+}
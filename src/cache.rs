@@ -0,0 +1,209 @@
+use crate::{EthereumError, UseEthereumHandle};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use web3::{
+    futures::{
+        future::{LocalBoxFuture, Shared},
+        FutureExt,
+    },
+    types::{H160, U256},
+};
+
+/// Cache of ERC20 `decimals()`, keyed by chain id and token address.
+///
+/// A token's decimals never change, so once read they are kept for the lifetime of the app.
+/// `EthereumContextProvider` provides one shared instance to all components via context, see
+/// `use_erc20_decimals_cache`.
+#[derive(Clone, Debug, Default)]
+pub struct Erc20DecimalsCache {
+    decimals: Rc<RefCell<HashMap<(u64, H160), u8>>>,
+}
+
+impl PartialEq for Erc20DecimalsCache {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.decimals, &other.decimals)
+    }
+}
+
+impl Erc20DecimalsCache {
+    pub fn get(&self, chain_id: u64, token: H160) -> Option<u8> {
+        self.decimals.borrow().get(&(chain_id, token)).copied()
+    }
+
+    pub fn insert(&self, chain_id: u64, token: H160, decimals: u8) {
+        self.decimals
+            .borrow_mut()
+            .insert((chain_id, token), decimals);
+    }
+
+    /// decimals of `token` on the handle's current chain, only calling the contract on a miss.
+    /// nothing is cached while the chain id is unknown
+    pub async fn decimals(
+        &self,
+        ethereum: &UseEthereumHandle,
+        token: H160,
+    ) -> Result<u8, EthereumError> {
+        let chain_id = ethereum.chain_id();
+        if let Some(decimals) = chain_id.and_then(|chain_id| self.get(chain_id, token)) {
+            return Ok(decimals);
+        }
+
+        let decimals = ethereum.erc20_decimals(token).await?;
+        if let Some(chain_id) = chain_id {
+            self.insert(chain_id, token, decimals);
+        }
+        Ok(decimals)
+    }
+}
+
+/// how long a cached balance is served before it is read again, in milliseconds
+pub const DEFAULT_BALANCE_MAX_AGE_MS: u32 = 4_000;
+
+/// chain id, account and token (`None` for the native currency)
+type BalanceKey = (u64, H160, Option<H160>);
+type PendingBalance = Shared<LocalBoxFuture<'static, Result<U256, EthereumError>>>;
+
+#[derive(Default)]
+struct BalanceCacheState {
+    /// balance and `Date.now()` of when it was read
+    balances: HashMap<BalanceKey, (U256, f64)>,
+    /// reads in flight, joined by every consumer asking for the same balance meanwhile
+    pending: HashMap<BalanceKey, (u64, PendingBalance)>,
+    /// id of the last read started
+    requests: u64,
+    /// bumped by `clear` so reads started before aren't cached
+    generation: u64,
+}
+
+/// Cache of native and ERC20 balances shared by all components under a
+/// `BalanceCacheProvider`, see `use_balance_cache`.
+///
+/// Consumers polling the same balance are served one read every `max_age_ms`: a fresh cached
+/// value is returned as is and concurrent misses join the read already in flight.
+#[derive(Clone)]
+pub struct BalanceCache {
+    max_age_ms: u32,
+    state: Rc<RefCell<BalanceCacheState>>,
+}
+
+impl PartialEq for BalanceCache {
+    fn eq(&self, other: &Self) -> bool {
+        self.max_age_ms == other.max_age_ms && Rc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl Default for BalanceCache {
+    fn default() -> Self {
+        BalanceCache::new(DEFAULT_BALANCE_MAX_AGE_MS)
+    }
+}
+
+impl BalanceCache {
+    pub fn new(max_age_ms: u32) -> BalanceCache {
+        BalanceCache {
+            max_age_ms,
+            state: Rc::default(),
+        }
+    }
+
+    /// balance of `account` in `token`, or the native currency for `None`, on the handle's
+    /// current chain. nothing is cached while the chain id is unknown
+    pub async fn balance(
+        &self,
+        ethereum: &UseEthereumHandle,
+        account: H160,
+        token: Option<H160>,
+    ) -> Result<U256, EthereumError> {
+        let chain_id = match ethereum.chain_id() {
+            Some(chain_id) => chain_id,
+            None => return fetch_balance(ethereum.clone(), account, token).await,
+        };
+        let key = (chain_id, account, token);
+
+        let (request, generation, pending) = {
+            let mut state = self.state.borrow_mut();
+            if let Some((balance, read_at)) = state.balances.get(&key) {
+                if js_sys::Date::now() - read_at < self.max_age_ms as f64 {
+                    return Ok(*balance);
+                }
+            }
+            let generation = state.generation;
+            match state.pending.get(&key) {
+                Some((request, pending)) => (*request, generation, pending.clone()),
+                None => {
+                    state.requests += 1;
+                    let request = state.requests;
+                    let pending = fetch_balance(ethereum.clone(), account, token)
+                        .boxed_local()
+                        .shared();
+                    state.pending.insert(key, (request, pending.clone()));
+                    (request, generation, pending)
+                }
+            }
+        };
+
+        let result = pending.await;
+        let mut state = self.state.borrow_mut();
+        if state.pending.get(&key).map(|(id, _)| *id) == Some(request) {
+            state.pending.remove(&key);
+            if let (Ok(balance), true) = (&result, state.generation == generation) {
+                state.balances.insert(key, (*balance, js_sys::Date::now()));
+            }
+        }
+        result
+    }
+
+    /// forget the balances of `account`, eg. after it sent a transaction
+    pub fn invalidate(&self, account: H160) {
+        let mut state = self.state.borrow_mut();
+        state
+            .balances
+            .retain(|(_, cached, _), _| *cached != account);
+        state.pending.retain(|(_, cached, _), _| *cached != account);
+    }
+
+    /// forget every balance, done by `BalanceCacheProvider` when the account or chain changes
+    pub fn clear(&self) {
+        let mut state = self.state.borrow_mut();
+        state.balances.clear();
+        state.pending.clear();
+        state.generation += 1;
+    }
+}
+
+async fn fetch_balance(
+    ethereum: UseEthereumHandle,
+    account: H160,
+    token: Option<H160>,
+) -> Result<U256, EthereumError> {
+    match token {
+        Some(token) => ethereum.erc20_balance_of(token, account).await,
+        None => Ok(ethereum.get_balance(account).await?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimals_are_kept_per_chain() {
+        let cache = Erc20DecimalsCache::default();
+        let token = H160::repeat_byte(0x11);
+        assert_eq!(cache.get(1, token), None);
+        cache.insert(1, token, 6);
+        assert_eq!(cache.get(1, token), Some(6));
+        // the same address on another chain is another token
+        assert_eq!(cache.get(137, token), None);
+        assert_eq!(cache.get(1, H160::repeat_byte(0x22)), None);
+    }
+
+    #[test]
+    fn clones_share_the_cache() {
+        let cache = Erc20DecimalsCache::default();
+        let shared = cache.clone();
+        shared.insert(1, H160::repeat_byte(0x11), 18);
+        assert_eq!(cache.get(1, H160::repeat_byte(0x11)), Some(18));
+        assert_eq!(cache, shared);
+        assert_ne!(cache, Erc20DecimalsCache::default());
+    }
+}
@@ -1,22 +1,443 @@
-use crate::{base_currency, Chain};
+use crate::{base_currency, BaseCurrency, Chain};
+use std::fmt;
+use web3::types::U256;
 
+/// A widely used network whose parameters ship with the crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KnownChain {
+    Ethereum,
+    Sepolia,
+    Polygon,
+    Arbitrum,
+    Optimism,
+    Base,
+    Avalanche,
+    AvalancheFuji,
+    BnbChain,
+    Gnosis,
+}
 
-pub fn ethereum() -> Chain {
-    Chain {
-        chain_id: "0x1".into(),
-        chain_name: "Ethereum".into(),
-        rpc_urls: [String::from("https://api.avax-test.network/ext/bc/C/rpc")],
-        native_currency: base_currency::eth(),
-        block_explorer_urls: Some([String::from("https://api.avax-test.network/ext/bc/C/rpc")]),
+impl KnownChain {
+    pub const ALL: [KnownChain; 10] = [
+        KnownChain::Ethereum,
+        KnownChain::Sepolia,
+        KnownChain::Polygon,
+        KnownChain::Arbitrum,
+        KnownChain::Optimism,
+        KnownChain::Base,
+        KnownChain::Avalanche,
+        KnownChain::AvalancheFuji,
+        KnownChain::BnbChain,
+        KnownChain::Gnosis,
+    ];
+
+    /// decimal chain id
+    pub fn chain_id(&self) -> u64 {
+        match self {
+            KnownChain::Ethereum => 1,
+            KnownChain::Sepolia => 11_155_111,
+            KnownChain::Polygon => 137,
+            KnownChain::Arbitrum => 42_161,
+            KnownChain::Optimism => 10,
+            KnownChain::Base => 8_453,
+            KnownChain::Avalanche => 43_114,
+            KnownChain::AvalancheFuji => 43_113,
+            KnownChain::BnbChain => 56,
+            KnownChain::Gnosis => 100,
+        }
+    }
+
+    pub fn from_chain_id(chain_id: u64) -> Option<KnownChain> {
+        KnownChain::ALL
+            .into_iter()
+            .find(|known| known.chain_id() == chain_id)
+    }
+
+    /// human readable name, eg. for showing the current network
+    pub fn name(&self) -> &'static str {
+        match self {
+            KnownChain::Ethereum => "Ethereum",
+            KnownChain::Sepolia => "Sepolia",
+            KnownChain::Polygon => "Polygon",
+            KnownChain::Arbitrum => "Arbitrum One",
+            KnownChain::Optimism => "OP Mainnet",
+            KnownChain::Base => "Base",
+            KnownChain::Avalanche => "Avalanche C-Chain",
+            KnownChain::AvalancheFuji => "Avalanche Fuji Testnet",
+            KnownChain::BnbChain => "BNB Smart Chain",
+            KnownChain::Gnosis => "Gnosis",
+        }
+    }
+
+    /// a public rpc endpoint, rate limited and meant for adding the chain to a wallet rather
+    /// than for heavy reads
+    pub fn rpc_url(&self) -> &'static str {
+        match self {
+            KnownChain::Ethereum => "https://ethereum-rpc.publicnode.com",
+            KnownChain::Sepolia => "https://ethereum-sepolia-rpc.publicnode.com",
+            KnownChain::Polygon => "https://polygon-rpc.com",
+            KnownChain::Arbitrum => "https://arb1.arbitrum.io/rpc",
+            KnownChain::Optimism => "https://mainnet.optimism.io",
+            KnownChain::Base => "https://mainnet.base.org",
+            KnownChain::Avalanche => "https://api.avax.network/ext/bc/C/rpc",
+            KnownChain::AvalancheFuji => "https://api.avax-test.network/ext/bc/C/rpc",
+            KnownChain::BnbChain => "https://bsc-dataseed.bnbchain.org",
+            KnownChain::Gnosis => "https://rpc.gnosischain.com",
+        }
+    }
+
+    pub fn explorer_url(&self) -> &'static str {
+        match self {
+            KnownChain::Ethereum => "https://etherscan.io",
+            KnownChain::Sepolia => "https://sepolia.etherscan.io",
+            KnownChain::Polygon => "https://polygonscan.com",
+            KnownChain::Arbitrum => "https://arbiscan.io",
+            KnownChain::Optimism => "https://optimistic.etherscan.io",
+            KnownChain::Base => "https://basescan.org",
+            KnownChain::Avalanche => "https://snowtrace.io",
+            KnownChain::AvalancheFuji => "https://testnet.snowtrace.io",
+            KnownChain::BnbChain => "https://bscscan.com",
+            KnownChain::Gnosis => "https://gnosisscan.io",
+        }
+    }
+
+    pub fn native_currency(&self) -> BaseCurrency {
+        let currency = |name: &str, symbol: &str| BaseCurrency {
+            name: String::from(name),
+            symbol: String::from(symbol),
+            decimals: 18,
+        };
+        match self {
+            KnownChain::Ethereum
+            | KnownChain::Arbitrum
+            | KnownChain::Optimism
+            | KnownChain::Base => base_currency::eth(),
+            KnownChain::Sepolia => currency("Sepolia Ether", "ETH"),
+            KnownChain::Polygon => currency("POL", "POL"),
+            KnownChain::Avalanche | KnownChain::AvalancheFuji => base_currency::avax(),
+            KnownChain::BnbChain => currency("BNB", "BNB"),
+            KnownChain::Gnosis => currency("xDAI", "XDAI"),
+        }
+    }
+
+    /// the descriptor for `add_chain` and the switch components
+    pub fn chain(&self) -> Chain {
+        Chain {
+            chain_id: format!("0x{:x}", self.chain_id()),
+            chain_name: String::from(self.name()),
+            rpc_urls: [String::from(self.rpc_url())],
+            native_currency: self.native_currency(),
+            block_explorer_urls: Some([String::from(self.explorer_url())]),
+        }
     }
 }
 
+impl fmt::Display for KnownChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+pub fn ethereum() -> Chain {
+    KnownChain::Ethereum.chain()
+}
+
 pub fn avalanche_testnet() -> Chain {
-    Chain {
-        chain_name: String::from("Avalanche Fuji Testnet"),
-        chain_id: String::from("0xA869"),
-        rpc_urls: [String::from("https://api.avax-test.network/ext/bc/C/rpc")],
-        native_currency: base_currency::avax(),
-        block_explorer_urls: Some([String::from("https://api.avax-test.network/ext/bc/C/rpc")]),
+    KnownChain::AvalancheFuji.chain()
+}
+
+/// all chains this crate ships descriptors for
+pub fn all() -> Vec<Chain> {
+    KnownChain::ALL.iter().map(KnownChain::chain).collect()
+}
+
+/// look up a known chain by its decimal chain id
+pub fn from_chain_id(chain_id: u64) -> Option<Chain> {
+    all().into_iter().find(|chain| chain.id() == Some(chain_id))
+}
+
+/// a chain id as wallets report it, either decimal (`"137"`) or `0x`-prefixed hex (`"0x89"`)
+pub fn parse_chain_id(chain_id: &str) -> Option<U256> {
+    let chain_id = chain_id.trim();
+    match chain_id
+        .strip_prefix("0x")
+        .or_else(|| chain_id.strip_prefix("0X"))
+    {
+        Some(hex) => U256::from_str_radix(hex, 16).ok(),
+        None => U256::from_dec_str(chain_id).ok(),
+    }
+}
+
+/// Something about a chain that may mean it isn't what it claims to be, for a caution prompt
+/// before adding it to the wallet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainWarning {
+    /// the rpc endpoint is plain http, so responses can be tampered with in transit
+    InsecureRpc,
+    /// the block explorer is plain http
+    InsecureExplorer,
+    /// the chain id is that of a known chain, but the name or currency differ from it
+    KnownChainMismatch { known_name: String },
+    /// the name is that of a known chain, but the chain id differs from it
+    ImpersonatesKnownChain { known_chain_id: u64 },
+    /// the currency symbol is that of a known chain, but the chain id differs from it
+    SuspiciousSymbol(String),
+    /// the rpc endpoint serves another chain than the chain id says
+    RpcChainIdMismatch { reported: u64 },
+}
+
+impl fmt::Display for ChainWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainWarning::InsecureRpc => write!(f, "the rpc endpoint doesn't use https"),
+            ChainWarning::InsecureExplorer => write!(f, "the block explorer doesn't use https"),
+            ChainWarning::KnownChainMismatch { known_name } => {
+                write!(f, "the chain id belongs to {}", known_name)
+            }
+            ChainWarning::ImpersonatesKnownChain { known_chain_id } => {
+                write!(f, "the name belongs to chain {}", known_chain_id)
+            }
+            ChainWarning::SuspiciousSymbol(symbol) => {
+                write!(f, "{} is the currency of another chain", symbol)
+            }
+            ChainWarning::RpcChainIdMismatch { reported } => {
+                write!(f, "the rpc endpoint serves chain {}", reported)
+            }
+        }
+    }
+}
+
+/// warnings about `chain` found without any request, by comparing it to the chains this crate
+/// knows. see `UseEthereumHandle::add_chain_checked` to also ask the rpc endpoint
+pub fn warnings(chain: &Chain) -> Vec<ChainWarning> {
+    let mut warnings = Vec::new();
+    if chain
+        .rpc_urls
+        .iter()
+        .any(|url| !url.trim().starts_with("https://"))
+    {
+        warnings.push(ChainWarning::InsecureRpc);
+    }
+    if let Some(urls) = &chain.block_explorer_urls {
+        if urls.iter().any(|url| !url.trim().starts_with("https://")) {
+            warnings.push(ChainWarning::InsecureExplorer);
+        }
+    }
+
+    let id = chain.id();
+    let name = chain.chain_name.trim().to_lowercase();
+    let symbol = chain.native_currency.symbol.trim().to_uppercase();
+    let known_chains = all();
+    let unknown_id = !known_chains.iter().any(|known| known.id() == id);
+    for known in &known_chains {
+        if known.id() == id {
+            if known.chain_name.to_lowercase() != name
+                || known.native_currency.symbol.to_uppercase() != symbol
+            {
+                warnings.push(ChainWarning::KnownChainMismatch {
+                    known_name: known.chain_name.clone(),
+                });
+            }
+            continue;
+        }
+        if known.chain_name.to_lowercase() == name {
+            warnings.push(ChainWarning::ImpersonatesKnownChain {
+                known_chain_id: known.id().unwrap_or_default(),
+            });
+        }
+        let suspicious = ChainWarning::SuspiciousSymbol(symbol.clone());
+        // several known chains share a currency, one warning about it is enough
+        if unknown_id
+            && known.native_currency.symbol.to_uppercase() == symbol
+            && !warnings.contains(&suspicious)
+        {
+            warnings.push(suspicious);
+        }
+    }
+    warnings
+}
+
+/// Field of a `ChainBuilder` that failed validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainField {
+    ChainId,
+    Name,
+    RpcUrl,
+    Symbol,
+    ExplorerUrl,
+}
+
+/// Why a `ChainBuilder` can't build its chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainBuildError {
+    pub field: ChainField,
+    pub reason: String,
+}
+
+impl fmt::Display for ChainBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.field, self.reason)
+    }
+}
+
+impl std::error::Error for ChainBuildError {}
+
+/// Builds a `Chain` from user input, validating it against what `wallet_addEthereumChain`
+/// accepts
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChainBuilder {
+    chain_id: Option<u64>,
+    name: String,
+    rpc_url: String,
+    currency_name: Option<String>,
+    symbol: String,
+    decimals: Option<u32>,
+    explorer_url: Option<String>,
+}
+
+impl ChainBuilder {
+    pub fn new() -> ChainBuilder {
+        ChainBuilder::default()
+    }
+
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn rpc_url(mut self, url: impl Into<String>) -> Self {
+        self.rpc_url = url.into();
+        self
+    }
+
+    /// ticker of the native currency, also used as its name unless `currency_name` is set
+    pub fn symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.symbol = symbol.into();
+        self
+    }
+
+    pub fn currency_name(mut self, name: impl Into<String>) -> Self {
+        self.currency_name = Some(name.into());
+        self
+    }
+
+    /// decimals of the native currency, 18 if unset
+    pub fn decimals(mut self, decimals: u32) -> Self {
+        self.decimals = Some(decimals);
+        self
+    }
+
+    pub fn explorer_url(mut self, url: impl Into<String>) -> Self {
+        self.explorer_url = Some(url.into());
+        self
+    }
+
+    /// every problem with the fields set so far, empty if `build` would succeed
+    pub fn validate(&self) -> Vec<ChainBuildError> {
+        let mut errors = Vec::new();
+        let mut fail = |field, reason: &str| {
+            errors.push(ChainBuildError {
+                field,
+                reason: String::from(reason),
+            })
+        };
+
+        if self.chain_id.unwrap_or(0) == 0 {
+            fail(ChainField::ChainId, "chain id is required");
+        }
+        if self.name.trim().is_empty() {
+            fail(ChainField::Name, "name is required");
+        }
+        if !is_http_url(&self.rpc_url) {
+            fail(
+                ChainField::RpcUrl,
+                "rpc url must start with https:// or http://",
+            );
+        }
+        // EIP-3085 limits the symbol to 2-6 characters
+        if !(2..=6).contains(&self.symbol.trim().chars().count()) {
+            fail(ChainField::Symbol, "symbol must be 2 to 6 characters long");
+        }
+        if let Some(url) = &self.explorer_url {
+            if !is_http_url(url) {
+                fail(
+                    ChainField::ExplorerUrl,
+                    "explorer url must start with https:// or http://",
+                );
+            }
+        }
+        errors
+    }
+
+    pub fn build(&self) -> Result<Chain, Vec<ChainBuildError>> {
+        let errors = self.validate();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let symbol = self.symbol.trim().to_string();
+        Ok(Chain {
+            chain_id: format!("0x{:x}", self.chain_id.unwrap_or_default()),
+            chain_name: self.name.trim().to_string(),
+            rpc_urls: [self.rpc_url.trim().to_string()],
+            native_currency: BaseCurrency {
+                name: self.currency_name.clone().unwrap_or_else(|| symbol.clone()),
+                symbol,
+                decimals: self.decimals.unwrap_or(18),
+            },
+            block_explorer_urls: self
+                .explorer_url
+                .as_ref()
+                .map(|url| [url.trim().to_string()]),
+        })
+    }
+}
+
+fn is_http_url(url: &str) -> bool {
+    let url = url.trim();
+    ["https://", "http://"]
+        .iter()
+        .any(|scheme| url.len() > scheme.len() && url.starts_with(scheme))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_decimal_and_hex_chain_ids() {
+        assert_eq!(parse_chain_id("137"), Some(U256::from(137)));
+        assert_eq!(parse_chain_id("0x89"), parse_chain_id("137"));
+        assert_eq!(parse_chain_id("0X89"), Some(U256::from(137)));
+        assert_eq!(parse_chain_id(" 1 "), Some(U256::one()));
+    }
+
+    #[test]
+    fn parse_malformed_chain_ids() {
+        assert_eq!(parse_chain_id(""), None);
+        assert_eq!(parse_chain_id("0x"), None);
+        assert_eq!(parse_chain_id("0xzz"), None);
+        assert_eq!(parse_chain_id("polygon"), None);
+    }
+
+    #[test]
+    fn warns_about_a_shared_symbol_once() {
+        let impostor = Chain {
+            chain_id: String::from("0x1234"),
+            ..KnownChain::Base.chain()
+        };
+        assert_eq!(
+            warnings(&impostor),
+            vec![
+                ChainWarning::SuspiciousSymbol(String::from("ETH")),
+                ChainWarning::ImpersonatesKnownChain {
+                    known_chain_id: 8453
+                },
+            ]
+        );
     }
 }
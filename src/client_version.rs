@@ -0,0 +1,33 @@
+/// A `web3_clientVersion` string split into its parts.
+///
+/// Nodes report `name/version/os/runtime`, eg. `Geth/v1.10.26-stable/linux-amd64/go1.19.3`,
+/// while wallets usually only report `name/version`, eg. `MetaMask/v10.25.0`. Missing parts are
+/// `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientVersion {
+    /// client name, eg. `Geth`, `Nethermind` or `MetaMask`
+    pub name: String,
+    /// version without the leading `v`
+    pub version: Option<String>,
+    pub os: Option<String>,
+    pub runtime: Option<String>,
+}
+
+impl ClientVersion {
+    pub fn parse(raw: &str) -> ClientVersion {
+        let mut parts = raw.trim().split('/').map(str::to_string);
+        ClientVersion {
+            name: parts.next().unwrap_or_default(),
+            version: parts
+                .next()
+                .map(|version| version.trim_start_matches('v').to_string()),
+            os: parts.next(),
+            runtime: parts.next(),
+        }
+    }
+
+    /// case-insensitive comparison of the client name, eg. `is("geth")`
+    pub fn is(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+}
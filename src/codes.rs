@@ -0,0 +1,44 @@
+//! Error codes of EIP-1193 providers and the json-rpc errors they forward, for matching on
+//! `EthereumError::Rpc { code, .. }`
+//! - https://eips.ethereum.org/EIPS/eip-1193#provider-errors
+//! - https://eips.ethereum.org/EIPS/eip-1474#error-codes
+
+/// the user rejected the request
+pub const USER_REJECTED: i64 = 4001;
+/// the requested method or account has not been authorized by the user
+pub const UNAUTHORIZED: i64 = 4100;
+/// the provider does not support the requested method
+pub const UNSUPPORTED_METHOD: i64 = 4200;
+/// the provider is disconnected from all chains
+pub const DISCONNECTED: i64 = 4900;
+/// the provider is not connected to the requested chain
+pub const CHAIN_DISCONNECTED: i64 = 4901;
+/// the wallet doesn't know the chain, add it with `wallet_addEthereumChain` first
+pub const UNRECOGNIZED_CHAIN: i64 = 4902;
+/// emitted by older metamask versions on `disconnect` (websocket close code)
+pub const LEGACY_DISCONNECTED: i64 = 1013;
+
+/// the call reverted, the error data holds the revert payload
+pub const EXECUTION_REVERTED: i64 = 3;
+/// missing or invalid parameters
+pub const INVALID_INPUT: i64 = -32000;
+/// requested resource not found
+pub const RESOURCE_NOT_FOUND: i64 = -32001;
+/// requested resource not available, eg. a prompt for the same request is already open
+pub const RESOURCE_UNAVAILABLE: i64 = -32002;
+/// transaction creation failed
+pub const TRANSACTION_REJECTED: i64 = -32003;
+/// method is not implemented
+pub const METHOD_NOT_SUPPORTED: i64 = -32004;
+/// request exceeds a defined limit, eg. the block range of `eth_getLogs`
+pub const LIMIT_EXCEEDED: i64 = -32005;
+/// invalid json was received
+pub const PARSE_ERROR: i64 = -32700;
+/// the json sent is not a valid request object
+pub const INVALID_REQUEST: i64 = -32600;
+/// the method does not exist
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// invalid method parameters
+pub const INVALID_PARAMS: i64 = -32602;
+/// internal json-rpc error. metamask wraps most node errors in this
+pub const INTERNAL_ERROR: i64 = -32603;
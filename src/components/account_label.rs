@@ -1,17 +1,73 @@
-use crate::hooks::UseEthereumHandle;
-use yew::prelude::*;
+use crate::{
+    hooks::{use_balance_cache, UseEthereumHandle},
+    units, BaseCurrency,
+};
+use web3::types::U256;
+use yew::{platform::spawn_local, prelude::*};
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// show the native currency balance of the account next to its address
+    #[prop_or_default]
+    pub show_balance: bool,
+
+    /// maximum number of fractional digits shown for the balance
+    #[prop_or(4)]
+    pub balance_precision: usize,
+}
 
 #[function_component]
-pub fn AccountLabel() -> Html {
+pub fn AccountLabel(props: &Props) -> Html {
     let ethereum = use_context::<Option<UseEthereumHandle>>().expect(
         "no ethereum provider found. you must wrap your components in an <EthereumProvider/>",
     );
+    let balance = use_state(|| None as Option<U256>);
+    let balance_cache = use_balance_cache();
+
+    {
+        let balance = balance.clone();
+        let handle = ethereum.clone();
+        let address = ethereum.as_ref().and_then(|e| e.address().cloned());
+        let chain_id = ethereum.as_ref().and_then(UseEthereumHandle::chain_id);
+        use_effect_with_deps(
+            move |(show_balance, address, _)| {
+                match (show_balance, handle, address) {
+                    (true, Some(handle), Some(address)) => {
+                        let address = *address;
+                        spawn_local(async move {
+                            let result = match balance_cache {
+                                Some(cache) => cache.balance(&handle, address, None).await,
+                                None => handle.get_balance(address).await,
+                            };
+                            match result {
+                                Ok(value) => balance.set(Some(value)),
+                                Err(err) => {
+                                    log::error!("failed to fetch balance: {}", err);
+                                    balance.set(None);
+                                }
+                            }
+                        });
+                    }
+                    _ => balance.set(None),
+                }
+                || ()
+            },
+            (props.show_balance, address, chain_id),
+        );
+    }
 
     html! {
         <div>
             if let Some(ethereum) = ethereum {
                 if ethereum.connected() {
                     {ethereum.display_address()}
+                    if let Some(value) = *balance {
+                        <span class="balance">
+                            {" "}{display_balance(&ethereum, value, props.balance_precision)}
+                        </span>
+                    }
+                } else if !ethereum.provider_is_connected() {
+                    {"RPC unavailable"}
                 } else {
                     {"Disconnected"}
                 }
@@ -21,3 +77,65 @@ pub fn AccountLabel() -> Html {
         </div>
     }
 }
+
+/// formats a balance using the decimals of the chain's native currency, falling back to 18 for
+/// chains missing from the registry
+fn display_balance(ethereum: &UseEthereumHandle, value: U256, precision: usize) -> String {
+    format_balance(value, ethereum.native_currency().as_ref(), precision)
+}
+
+fn format_balance(value: U256, currency: Option<&BaseCurrency>, precision: usize) -> String {
+    match currency {
+        Some(currency) => format!(
+            "{} {}",
+            units::format_units_truncated(value, currency.decimals, precision),
+            currency.symbol
+        ),
+        None => units::format_units_truncated(value, 18, precision),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn currency(symbol: &str, decimals: u32) -> BaseCurrency {
+        BaseCurrency {
+            name: String::from(symbol),
+            symbol: String::from(symbol),
+            decimals,
+        }
+    }
+
+    #[test]
+    fn six_decimal_native_currency() {
+        let usd = currency("USD", 6);
+        assert_eq!(
+            format_balance(U256::from(1_234_567), Some(&usd), 4),
+            "1.2345 USD"
+        );
+        assert_eq!(
+            format_balance(U256::from(2_000_000), Some(&usd), 4),
+            "2 USD"
+        );
+    }
+
+    #[test]
+    fn eight_decimal_native_currency() {
+        let btc = currency("BTC", 8);
+        assert_eq!(
+            format_balance(U256::from(150_000_000), Some(&btc), 4),
+            "1.5 BTC"
+        );
+        assert_eq!(
+            format_balance(U256::from(1), Some(&btc), 8),
+            "0.00000001 BTC"
+        );
+    }
+
+    #[test]
+    fn unknown_currency_assumes_18_decimals() {
+        let value = U256::from(1_500_000_000_000_000_000u64);
+        assert_eq!(format_balance(value, None, 4), "1.5");
+    }
+}
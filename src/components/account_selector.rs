@@ -0,0 +1,51 @@
+use crate::{hooks::UseEthereumHandle, AddressStyle};
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// how each account is shown
+    #[prop_or(AddressStyle::Short { lead: 4, trail: 4 })]
+    pub style: AddressStyle,
+
+    #[prop_or_default]
+    pub class: Option<String>,
+}
+
+/// A drop-down of the accounts the wallet shares, selecting the one `address()` returns.
+/// renders nothing unless there are several accounts to choose from
+#[function_component]
+pub fn AccountSelector(props: &Props) -> Html {
+    let ethereum = use_context::<Option<UseEthereumHandle>>().expect(
+        "no ethereum provider found. you must wrap your components in an <EthereumProvider/>",
+    );
+    let ethereum = match ethereum {
+        Some(ethereum) if ethereum.connected() => ethereum,
+        _ => return html! {},
+    };
+    let accounts = match ethereum.accounts() {
+        Some(accounts) if accounts.len() > 1 => accounts.clone(),
+        _ => return html! {},
+    };
+
+    let selected = ethereum.selected_account();
+    let onchange = {
+        let ethereum = ethereum.clone();
+        Callback::from(move |e: Event| {
+            let select = e.target_unchecked_into::<HtmlSelectElement>();
+            if let Ok(index) = select.value().parse() {
+                ethereum.select_account(index);
+            }
+        })
+    };
+
+    html! {
+        <select class={&props.class} {onchange}>
+            {for accounts.iter().enumerate().map(|(index, account)| html! {
+                <option value={index.to_string()} selected={index == selected}>
+                    {crate::format_address(account, props.style, None)}
+                </option>
+            })}
+        </select>
+    }
+}
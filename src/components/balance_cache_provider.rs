@@ -0,0 +1,43 @@
+use crate::{hooks::UseEthereumHandle, BalanceCache, DEFAULT_BALANCE_MAX_AGE_MS};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    #[prop_or_default]
+    pub children: Children,
+
+    /// serve a cached balance for this many milliseconds before reading it again
+    #[prop_or(DEFAULT_BALANCE_MAX_AGE_MS)]
+    pub max_age_ms: u32,
+}
+
+/// Shares balance reads between the components below it, see `BalanceCache`. must be placed
+/// inside an `EthereumContextProvider`. the cache is cleared whenever the account or chain
+/// changes
+#[function_component]
+pub fn BalanceCacheProvider(props: &Props) -> Html {
+    let ethereum = use_context::<Option<UseEthereumHandle>>().flatten();
+    let cache = use_memo(
+        |max_age_ms| BalanceCache::new(*max_age_ms),
+        props.max_age_ms,
+    );
+
+    {
+        let cache = (*cache).clone();
+        let address = ethereum.as_ref().and_then(|e| e.address().cloned());
+        let chain_id = ethereum.as_ref().and_then(UseEthereumHandle::chain_id);
+        use_effect_with_deps(
+            move |_| {
+                cache.clear();
+                || ()
+            },
+            (address, chain_id),
+        );
+    }
+
+    html! {
+        <ContextProvider<BalanceCache> context={(*cache).clone()}>
+            {for props.children.iter()}
+        </ContextProvider<BalanceCache>>
+    }
+}
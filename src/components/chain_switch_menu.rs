@@ -0,0 +1,179 @@
+use crate::{
+    chain::{self, ChainBuildError, ChainBuilder, ChainField},
+    hooks::{use_async_action, UseEthereumHandle},
+    Chain, EthereumError, SwitchNetworkButton,
+};
+use web3::types::U256;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// chains offered for switching, the ones shipped with the crate by default
+    #[prop_or_else(chain::all)]
+    pub chains: Vec<Chain>,
+
+    #[prop_or_default]
+    pub class: Option<String>,
+}
+
+#[derive(Clone, Default, PartialEq)]
+struct CustomChainForm {
+    name: String,
+    rpc_url: String,
+    chain_id: String,
+    symbol: String,
+    explorer_url: String,
+}
+
+impl CustomChainForm {
+    fn build(&self) -> Result<Chain, Vec<ChainBuildError>> {
+        let mut builder = ChainBuilder::new()
+            .name(self.name.as_str())
+            .rpc_url(self.rpc_url.as_str())
+            .symbol(self.symbol.as_str());
+        if !self.explorer_url.trim().is_empty() {
+            builder = builder.explorer_url(self.explorer_url.as_str());
+        }
+        // chain ids wider than 64 bits can't be added to a wallet
+        let chain_id = chain::parse_chain_id(&self.chain_id)
+            .filter(|chain_id| *chain_id <= U256::from(u64::MAX))
+            .map(|chain_id| chain_id.as_u64());
+        match chain_id {
+            Some(chain_id) => builder.chain_id(chain_id).build(),
+            None => {
+                let mut errors = builder.validate();
+                errors.retain(|error| error.field != ChainField::ChainId);
+                errors.insert(
+                    0,
+                    ChainBuildError {
+                        field: ChainField::ChainId,
+                        reason: String::from("chain id must be a decimal or 0x hex number"),
+                    },
+                );
+                Err(errors)
+            }
+        }
+    }
+}
+
+/// Lists `chains` to switch to, plus a form to add and switch to any other network. the form
+/// is validated with `ChainBuilder` and the chain added to the wallet if it doesn't know it yet
+#[function_component]
+pub fn ChainSwitchMenu(props: &Props) -> Html {
+    let ethereum = use_context::<Option<UseEthereumHandle>>().expect(
+        "no ethereum provider found. you must wrap your components in an <EthereumProvider/>",
+    );
+    let form = use_state(CustomChainForm::default);
+    let errors = use_state(Vec::<ChainBuildError>::new);
+    // the chain the form was last submitted with, read by the switch action
+    let target = use_mut_ref(|| None as Option<Chain>);
+    let switch = {
+        let ethereum = ethereum.clone();
+        let target = target.clone();
+        use_async_action(move || {
+            let ethereum = ethereum.clone();
+            let chain = target.borrow().clone();
+            async move {
+                match (ethereum, chain) {
+                    (Some(ethereum), Some(chain)) => {
+                        ethereum.switch_chain_with_fallback(&chain).await
+                    }
+                    (None, _) => Err(EthereumError::NoProvider),
+                    (Some(_), None) => Err(EthereumError::InvalidRequest(String::from(
+                        "no chain submitted",
+                    ))),
+                }
+            }
+        })
+    };
+
+    let ethereum = match ethereum {
+        Some(ethereum) => ethereum,
+        None => return html! {},
+    };
+
+    let input = |update: fn(&mut CustomChainForm, String)| {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let mut next = (*form).clone();
+            update(
+                &mut next,
+                e.target_unchecked_into::<HtmlInputElement>().value(),
+            );
+            form.set(next);
+        })
+    };
+    let onsubmit = {
+        let form = form.clone();
+        let errors = errors.clone();
+        let run = switch.run.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            match form.build() {
+                Ok(chain) => {
+                    errors.set(Vec::new());
+                    *target.borrow_mut() = Some(chain);
+                    run.emit(());
+                }
+                Err(found) => errors.set(found),
+            }
+        })
+    };
+    let field_error = |field: ChainField| {
+        errors
+            .iter()
+            .find(|error| error.field == field)
+            .map(|error| html! { <span class="error">{&error.reason}</span> })
+    };
+    let current = ethereum.chain_id();
+
+    html! {
+        <div class={&props.class}>
+            <ul>
+                {for props.chains.iter().map(|chain| html! {
+                    <li class={classes!((chain.id() == current).then_some("current"))}>
+                        <SwitchNetworkButton chain={chain.clone()} />
+                    </li>
+                })}
+            </ul>
+            <form {onsubmit}>
+                <label>
+                    {"Network name"}
+                    <input value={form.name.clone()} oninput={input(|form, value| form.name = value)} />
+                    {field_error(ChainField::Name)}
+                </label>
+                <label>
+                    {"RPC URL"}
+                    <input value={form.rpc_url.clone()} oninput={input(|form, value| form.rpc_url = value)} />
+                    {field_error(ChainField::RpcUrl)}
+                </label>
+                <label>
+                    {"Chain ID"}
+                    <input value={form.chain_id.clone()} oninput={input(|form, value| form.chain_id = value)} />
+                    {field_error(ChainField::ChainId)}
+                </label>
+                <label>
+                    {"Currency symbol"}
+                    <input value={form.symbol.clone()} oninput={input(|form, value| form.symbol = value)} />
+                    {field_error(ChainField::Symbol)}
+                </label>
+                <label>
+                    {"Block explorer URL (optional)"}
+                    <input value={form.explorer_url.clone()} oninput={input(|form, value| form.explorer_url = value)} />
+                    {field_error(ChainField::ExplorerUrl)}
+                </label>
+                <button type="submit" disabled={switch.pending}>
+                    if switch.pending {
+                        {"Adding network..."}
+                    } else {
+                        {"Add and switch"}
+                    }
+                </button>
+                if let Some(err) = &switch.error {
+                    <div class="error">{err.to_string()}</div>
+                }
+            </form>
+        </div>
+    }
+}
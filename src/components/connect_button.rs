@@ -1,37 +1,51 @@
 use yew::prelude::*;
 
-use crate::UseEthereumHandle;
+use crate::{hooks::use_async_action, ConnectionStatus, EthereumError, UseEthereumHandle};
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
     #[prop_or_default]
     pub children: Children,
     pub connected_html: Option<Html>,
+
+    #[prop_or_default]
+    pub class: Option<String>,
+
+    /// fired once a connection started by clicking this button succeeds
+    #[prop_or_default]
+    pub on_connect: Option<Callback<()>>,
 }
 
 #[function_component]
 pub fn ConnectButton(props: &Props) -> Html {
     let ethereum = use_context::<Option<UseEthereumHandle>>().expect(
-        "no ethereum ethereum found. you must wrap your components in an <Ethereumethereum/>",
+        "no ethereum provider found. you must wrap your components in an <EthereumProvider/>",
     );
-
-    if let Some(ethereum) = ethereum {
-        let connect = {
+    let connect = {
+        let ethereum = ethereum.clone();
+        let on_connect = props.on_connect.clone();
+        use_async_action(move || {
             let ethereum = ethereum.clone();
-            Callback::from(move |_| {
-                let ethereum = ethereum.clone();
-                wasm_bindgen_futures::spawn_local(async move {
-                    let _ = ethereum.connect().await;
-                });
-            })
-        };
+            let on_connect = on_connect.clone();
+            async move {
+                let ethereum = ethereum.ok_or(EthereumError::NoProvider)?;
+                ethereum.connect().await?;
+                if let Some(on_connect) = on_connect {
+                    on_connect.emit(());
+                }
+                Ok::<_, EthereumError>(())
+            }
+        })
+    };
 
+    if let Some(ethereum) = ethereum {
         let disconnect = {
             let ethereum = ethereum.clone();
             Callback::from(move |_| ethereum.disconnect())
         };
 
         let short_address = ethereum.display_short_address();
+        let connecting = connect.pending || ethereum.status() == ConnectionStatus::Connecting;
 
         let connected_html = props.connected_html.clone().unwrap_or_else(|| {
             html! {
@@ -43,17 +57,24 @@ pub fn ConnectButton(props: &Props) -> Html {
         });
 
         html! {
-            <div>
+            <div class={&props.class}>
                 if ethereum.connected() {
                     <button onclick={disconnect}>
                         {connected_html}
                     </button>
                 } else {
-                    <button onclick={connect}>
+                    <button onclick={connect.run.reform(|_| ())} disabled={connecting}>
                         <div class={classes!("btn", "btn-primary", "disconnected")}>
-                            {"Connect Wallet"}
+                            if connecting {
+                                {"Connecting..."}
+                            } else {
+                                {"Connect Wallet"}
+                            }
                         </div>
                     </button>
+                    if let Some(err) = &connect.error {
+                        <div class="error">{err.to_string()}</div>
+                    }
                 }
             </div>
         }
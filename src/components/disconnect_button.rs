@@ -0,0 +1,77 @@
+use crate::hooks::UseEthereumHandle;
+use std::time::Duration;
+use yew::{
+    platform::{spawn_local, time::sleep},
+    prelude::*,
+};
+
+/// how long a first click of a confirming `DisconnectButton` waits for the second one
+pub const DISCONNECT_CONFIRM_MS: u64 = 3000;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    #[prop_or_default]
+    pub class: Option<String>,
+    /// called once the wallet is disconnected
+    #[prop_or_default]
+    pub on_disconnect: Option<Callback<()>>,
+    /// require a second click within `DISCONNECT_CONFIRM_MS` before disconnecting
+    #[prop_or_default]
+    pub confirm: bool,
+}
+
+/// Disconnects the wallet, rendering nothing while there is no connection
+#[function_component]
+pub fn DisconnectButton(props: &Props) -> Html {
+    let ethereum = use_context::<Option<UseEthereumHandle>>().expect(
+        "no ethereum provider found. you must wrap your components in an <EthereumProvider/>",
+    );
+    let armed = use_state(|| false);
+    // bumped on every click so only the latest arming times out
+    let clicks = use_mut_ref(|| 0u32);
+
+    let ethereum = match ethereum {
+        Some(ethereum) if ethereum.connected() => ethereum,
+        _ => return html! {},
+    };
+
+    let onclick = {
+        let armed = armed.clone();
+        let confirm = props.confirm;
+        let on_disconnect = props.on_disconnect.clone();
+        Callback::from(move |_| {
+            let click = {
+                let mut clicks = clicks.borrow_mut();
+                *clicks = clicks.wrapping_add(1);
+                *clicks
+            };
+            if confirm && !*armed {
+                armed.set(true);
+                let armed = armed.clone();
+                let clicks = clicks.clone();
+                spawn_local(async move {
+                    sleep(Duration::from_millis(DISCONNECT_CONFIRM_MS)).await;
+                    if *clicks.borrow() == click {
+                        armed.set(false);
+                    }
+                });
+                return;
+            }
+            armed.set(false);
+            ethereum.disconnect();
+            if let Some(on_disconnect) = &on_disconnect {
+                on_disconnect.emit(());
+            }
+        })
+    };
+
+    html! {
+        <button class={&props.class} {onclick}>
+            if *armed {
+                {"Click again to disconnect"}
+            } else {
+                {"Disconnect"}
+            }
+        </button>
+    }
+}
@@ -1,5 +1,14 @@
-use crate::hooks::{use_ethereum, UseEthereumHandle};
-use yew::{function_component, html, Children, ContextProvider, Html, Properties};
+use crate::{
+    hooks::{
+        use_ethereum, use_read_only_ethereum, ReadOnlyConfig, UseEthereumHandle,
+        DEFAULT_EVENT_DEBOUNCE_MS,
+    },
+    Erc20DecimalsCache,
+};
+use yew::{
+    function_component, html, platform::spawn_local, use_effect_with_deps, use_state, Callback,
+    Children, ContextProvider, Html, Properties,
+};
 
 #[derive(Clone, PartialEq)]
 pub struct EthereumProviderState {
@@ -10,15 +19,126 @@ pub struct EthereumProviderState {
 pub struct Props {
     #[prop_or_default]
     pub children: Children,
+
+    /// read through this rpc endpoint when no wallet is injected
+    #[prop_or_default]
+    pub read_only: Option<ReadOnlyConfig>,
+
+    /// disconnect after this many milliseconds without any request
+    #[prop_or_default]
+    pub idle_timeout: Option<u32>,
+
+    /// fired when `idle_timeout` disconnects the session
+    #[prop_or_default]
+    pub onidle: Option<Callback<()>>,
+
+    /// mainnet rpc endpoint for ENS resolution while the wallet is on another chain
+    #[prop_or_default]
+    pub ens_rpc_url: Option<String>,
+
+    /// rpc endpoint for reads such as `eth_call` and `eth_getLogs`, see `set_read_rpc_url`
+    #[prop_or_default]
+    pub read_rpc_url: Option<String>,
+
+    /// coalesce bursts of `chainChanged`/`accountsChanged` events within this many milliseconds
+    #[prop_or(DEFAULT_EVENT_DEBOUNCE_MS)]
+    pub event_debounce_ms: u32,
+
+    /// remember the connection in localStorage and restore it without prompting on the next
+    /// page load, as long as the wallet still authorizes the site
+    #[prop_or_default]
+    pub persist_connection: bool,
 }
 
 #[function_component]
 pub fn EthereumContextProvider(props: &Props) -> Html {
-    let ethereum = use_ethereum(None);
+    let wallet = use_ethereum(None);
+    let read_only = use_read_only_ethereum(props.read_only.clone());
+    let ethereum = wallet.or(read_only);
+
+    {
+        let ethereum = ethereum.clone();
+        use_effect_with_deps(
+            move |(idle_timeout, onidle)| {
+                if let Some(ethereum) = ethereum {
+                    ethereum.set_on_idle(onidle.clone());
+                    ethereum.set_idle_timeout(*idle_timeout);
+                }
+                || ()
+            },
+            (props.idle_timeout, props.onidle.clone()),
+        );
+    }
+
+    {
+        let ethereum = ethereum.clone();
+        use_effect_with_deps(
+            move |ens_rpc_url| {
+                if let Some(ethereum) = ethereum {
+                    if let Err(err) = ethereum.set_ens_rpc_url(ens_rpc_url.as_deref()) {
+                        log::error!("invalid ens rpc url: {}", err);
+                    }
+                }
+                || ()
+            },
+            props.ens_rpc_url.clone(),
+        );
+    }
+
+    {
+        let ethereum = ethereum.clone();
+        use_effect_with_deps(
+            move |read_rpc_url| {
+                if let Some(ethereum) = ethereum {
+                    if let Err(err) = ethereum.set_read_rpc_url(read_rpc_url.as_deref()) {
+                        log::error!("invalid read rpc url: {}", err);
+                    }
+                }
+                || ()
+            },
+            props.read_rpc_url.clone(),
+        );
+    }
+
+    {
+        let ethereum = ethereum.clone();
+        use_effect_with_deps(
+            move |event_debounce_ms| {
+                if let Some(ethereum) = ethereum {
+                    ethereum.set_event_debounce(*event_debounce_ms);
+                }
+                || ()
+            },
+            props.event_debounce_ms,
+        );
+    }
+
+    {
+        let ethereum = ethereum.clone();
+        use_effect_with_deps(
+            move |persist_connection| {
+                if let Some(ethereum) = ethereum {
+                    ethereum.set_persist_connection(*persist_connection);
+                    if *persist_connection {
+                        spawn_local(async move {
+                            if let Err(err) = ethereum.restore_connection().await {
+                                log::warn!("could not restore the connection: {}", err);
+                            }
+                        });
+                    }
+                }
+                || ()
+            },
+            props.persist_connection,
+        );
+    }
+    let decimals_cache = use_state(Erc20DecimalsCache::default);
 
     html! {
         <ContextProvider<Option<UseEthereumHandle>> context={ethereum}>
-            {for props.children.iter()}
+            <ContextProvider<Erc20DecimalsCache> context={(*decimals_cache).clone()}>
+                {for props.children.iter()}
+            </ContextProvider<Erc20DecimalsCache>>
         </ContextProvider<Option<UseEthereumHandle>>>
     }
 }
@@ -0,0 +1,54 @@
+use crate::{hooks::UseEthereumHandle, EthereumError};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    #[prop_or_default]
+    pub children: Children,
+
+    /// renders the error instead of the default message and retry button
+    #[prop_or_default]
+    pub fallback: Option<Callback<EthereumError, Html>>,
+
+    /// fired when the user clicks retry, after the error has been cleared
+    #[prop_or_default]
+    pub onretry: Option<Callback<()>>,
+}
+
+/// Renders `handle.last_error()` in place of its children while there is one, with a retry
+/// button clearing it. only errors the provider reports are caught, a panic still aborts the app
+#[function_component]
+pub fn EthereumErrorBoundary(props: &Props) -> Html {
+    let ethereum = use_context::<Option<UseEthereumHandle>>().expect(
+        "no ethereum provider found. you must wrap your components in an <EthereumProvider/>",
+    );
+
+    let (ethereum, err) = match ethereum {
+        Some(ethereum) => match ethereum.last_error() {
+            Some(err) => (ethereum, err),
+            None => return html! { {for props.children.iter()} },
+        },
+        None => return html! { {for props.children.iter()} },
+    };
+
+    if let Some(fallback) = &props.fallback {
+        return fallback.emit(err);
+    }
+
+    let retry = {
+        let onretry = props.onretry.clone();
+        Callback::from(move |_| {
+            ethereum.clear_error();
+            if let Some(onretry) = &onretry {
+                onretry.emit(());
+            }
+        })
+    };
+
+    html! {
+        <div class="error">
+            <p>{err.to_string()}</p>
+            <button onclick={retry}>{"Retry"}</button>
+        </div>
+    }
+}
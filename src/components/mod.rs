@@ -1,9 +1,27 @@
 mod account_label;
+mod account_selector;
+mod balance_cache_provider;
+mod chain_switch_menu;
 mod connect_button;
+mod disconnect_button;
 mod ethereum_context_provider;
+mod ethereum_error_boundary;
+mod network_label;
 mod switch_network_button;
+mod token_transfer;
+mod transaction_history;
+mod web3_gate;
 
 pub use account_label::*;
+pub use account_selector::*;
+pub use balance_cache_provider::*;
+pub use chain_switch_menu::*;
 pub use connect_button::*;
+pub use disconnect_button::*;
 pub use ethereum_context_provider::*;
+pub use ethereum_error_boundary::*;
+pub use network_label::*;
 pub use switch_network_button::*;
+pub use token_transfer::*;
+pub use transaction_history::*;
+pub use web3_gate::*;
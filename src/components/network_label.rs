@@ -0,0 +1,33 @@
+use crate::{chain::KnownChain, hooks::UseEthereumHandle};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    #[prop_or_default]
+    pub class: Option<String>,
+}
+
+/// The name of the network the wallet is on, or "Chain {id}" for networks missing from
+/// `KnownChain`
+#[function_component]
+pub fn NetworkLabel(props: &Props) -> Html {
+    let ethereum = use_context::<Option<UseEthereumHandle>>().expect(
+        "no ethereum provider found. you must wrap your components in an <EthereumProvider/>",
+    );
+
+    let label = match ethereum {
+        Some(ethereum) if ethereum.connected() => match ethereum.chain_id() {
+            Some(chain_id) => match KnownChain::from_chain_id(chain_id) {
+                Some(known) => known.name().to_string(),
+                None => format!("Chain {}", chain_id),
+            },
+            None => String::from("Unknown network"),
+        },
+        Some(_) => String::from("Not connected"),
+        None => String::from("No ethereum provider found"),
+    };
+
+    html! {
+        <span class={&props.class}>{label}</span>
+    }
+}
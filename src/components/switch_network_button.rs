@@ -1,8 +1,7 @@
 use crate::{
-    hooks::UseEthereumHandle,
-    Chain,
+    hooks::{use_async_action, UseEthereumHandle},
+    Chain, EthereumError,
 };
-use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
@@ -16,28 +15,36 @@ pub struct Props {
 #[function_component]
 pub fn SwitchNetworkButton(props: &Props) -> Html {
     let ethereum = use_context::<Option<UseEthereumHandle>>().expect(
-        "no ethereum ethereum found. you must wrap your components in an <Ethereumethereum/>",
+        "no ethereum provider found. you must wrap your components in an <EthereumProvider/>",
     );
-
-    if let Some(ethereum) = ethereum {
+    let switch = {
+        let ethereum = ethereum.clone();
         let chain = props.chain.clone();
-
-        let on_click = {
+        use_async_action(move || {
             let ethereum = ethereum.clone();
-            Callback::from(move |_| {
-                let ethereum = ethereum.clone();
-                let chain = chain.clone();
-                spawn_local(async move {
-                    let _ = ethereum.switch_chain_with_fallback(&chain).await;
-                });
-            })
-        };
+            let chain = chain.clone();
+            async move {
+                match ethereum {
+                    Some(ethereum) => ethereum.switch_chain_with_fallback(&chain).await,
+                    None => Err(EthereumError::NoProvider),
+                }
+            }
+        })
+    };
 
+    if ethereum.is_some() {
         html! {
             <div>
-                <button onclick={on_click} class={&props.class}>
-                    {"Switch to "}{&props.chain.chain_name}
+                <button onclick={switch.run.reform(|_| ())} disabled={switch.pending} class={&props.class}>
+                    if switch.pending {
+                        {"Switching to "}{&props.chain.chain_name}{"..."}
+                    } else {
+                        {"Switch to "}{&props.chain.chain_name}
+                    }
                 </button>
+                if let Some(err) = &switch.error {
+                    <div class="error">{err.to_string()}</div>
+                }
             </div>
         }
     } else {
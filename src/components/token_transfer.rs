@@ -0,0 +1,187 @@
+use crate::{
+    chain,
+    hooks::{use_erc20_decimals_cache, use_transaction, UseEthereumHandle},
+    units, EthereumError, TransactionReceipt,
+};
+use std::{cell::Cell, rc::Rc};
+use web3::types::{H160, U256};
+use web_sys::HtmlInputElement;
+use yew::{platform::spawn_local, prelude::*};
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// ERC20 contract of the token sent
+    pub token: H160,
+
+    #[prop_or_default]
+    pub class: Option<String>,
+}
+
+#[derive(Clone, Default, PartialEq)]
+struct TransferForm {
+    recipient: String,
+    amount: String,
+}
+
+/// the transfer the form describes, or why it can't be sent
+fn validate(
+    form: &TransferForm,
+    decimals: Option<u8>,
+    balance: Option<U256>,
+) -> Result<(H160, U256), &'static str> {
+    let recipient = form
+        .recipient
+        .trim()
+        .parse::<H160>()
+        .map_err(|_| "enter a valid recipient address")?;
+    let decimals = decimals.ok_or("loading token details...")?;
+    let amount = units::parse_units(&form.amount, decimals as u32)
+        .filter(|amount| !amount.is_zero())
+        .ok_or("enter an amount")?;
+    match balance {
+        Some(balance) if amount > balance => Err("insufficient balance"),
+        Some(_) => Ok((recipient, amount)),
+        None => Err("loading balance..."),
+    }
+}
+
+/// Sends `token` from the connected account: a recipient and amount form, checked against the
+/// account's balance, whose submission calls `erc20_transfer` and waits for the transaction to
+/// be mined before showing its hash
+#[function_component]
+pub fn TokenTransfer(props: &Props) -> Html {
+    let ethereum = use_context::<Option<UseEthereumHandle>>().expect(
+        "no ethereum provider found. you must wrap your components in an <EthereumProvider/>",
+    );
+    let decimals_cache = use_erc20_decimals_cache();
+    let form = use_state(TransferForm::default);
+    let decimals = use_state(|| None as Option<u8>);
+    let balance = use_state(|| None as Option<U256>);
+
+    let transfer = {
+        let handle = ethereum.clone();
+        let token = props.token;
+        let validated = validate(&form, *decimals, *balance);
+        use_transaction(ethereum.clone(), 1, move || {
+            let ethereum = handle.clone();
+            async move {
+                let ethereum = ethereum.ok_or(EthereumError::NotConnected)?;
+                let (recipient, amount) =
+                    validated.map_err(|reason| EthereumError::InvalidRequest(reason.into()))?;
+                ethereum.erc20_transfer(token, recipient, amount).await
+            }
+        })
+    };
+    // the balance is read again once a transfer is mined
+    let confirmed = transfer
+        .receipt
+        .as_ref()
+        .map(TransactionReceipt::transaction_hash);
+
+    {
+        let decimals = decimals.clone();
+        let balance = balance.clone();
+        let handle = ethereum.clone();
+        let address = ethereum.as_ref().and_then(|e| e.address().cloned());
+        let chain_id = ethereum.as_ref().and_then(UseEthereumHandle::chain_id);
+        use_effect_with_deps(
+            move |(token, address, _, _)| {
+                balance.set(None);
+                // cleared when the deps change or the component unmounts, so reads for the
+                // previous token or account are dropped
+                let active = Rc::new(Cell::new(true));
+                if let (Some(handle), Some(address)) = (handle, *address) {
+                    let active = active.clone();
+                    let token = *token;
+                    spawn_local(async move {
+                        let read = decimals_cache.decimals(&handle, token).await;
+                        if !active.get() {
+                            return;
+                        }
+                        match read {
+                            Ok(value) => decimals.set(Some(value)),
+                            Err(err) => log::error!("failed to read token decimals: {}", err),
+                        }
+                        let read = handle.erc20_balance_of(token, address).await;
+                        if !active.get() {
+                            return;
+                        }
+                        match read {
+                            Ok(value) => balance.set(Some(value)),
+                            Err(err) => log::error!("failed to read token balance: {}", err),
+                        }
+                    });
+                }
+                move || active.set(false)
+            },
+            (props.token, address, chain_id, confirmed),
+        );
+    }
+
+    let ethereum = match ethereum {
+        Some(ethereum) if ethereum.connected() => ethereum,
+        _ => return html! {},
+    };
+
+    let input = |update: fn(&mut TransferForm, String)| {
+        let form = form.clone();
+        Callback::from(move |e: InputEvent| {
+            let mut next = (*form).clone();
+            update(
+                &mut next,
+                e.target_unchecked_into::<HtmlInputElement>().value(),
+            );
+            form.set(next);
+        })
+    };
+    let onsubmit = {
+        let send = transfer.send.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            send.emit(());
+        })
+    };
+    let validated = validate(&form, *decimals, *balance);
+    let explorer = ethereum.chain_id().and_then(chain::from_chain_id);
+
+    html! {
+        <form class={&props.class} {onsubmit}>
+            <label>
+                {"Recipient"}
+                <input value={form.recipient.clone()} oninput={input(|form, value| form.recipient = value)} />
+            </label>
+            <label>
+                {"Amount"}
+                <input value={form.amount.clone()} oninput={input(|form, value| form.amount = value)} />
+                if let (Some(balance), Some(decimals)) = (*balance, *decimals) {
+                    <span class="balance">
+                        {"Balance: "}{units::format_units_truncated(balance, decimals as u32, 4)}
+                    </span>
+                }
+            </label>
+            <button type="submit" disabled={transfer.pending || validated.is_err()}>
+                if transfer.pending && transfer.hash.is_some() {
+                    {"Confirming..."}
+                } else if transfer.pending {
+                    {"Sending..."}
+                } else {
+                    {"Send"}
+                }
+            </button>
+            if let Err(reason) = validated {
+                <div class="hint">{reason}</div>
+            }
+            if let Some(err) = &transfer.error {
+                <div class="error">{err.to_string()}</div>
+            }
+            if let Some(hash) = &confirmed {
+                <div class="confirmed">
+                    {"Confirmed "}{format!("{:?}", hash)}
+                    if let Some(link) = explorer.as_ref().and_then(|chain| chain.explorer_tx_url(hash)) {
+                        {" "}<a href={link} target="_blank" rel="noopener noreferrer">{"view"}</a>
+                    }
+                </div>
+            }
+        </form>
+    }
+}
@@ -0,0 +1,278 @@
+use crate::{
+    abi, chain, hooks::use_erc20_decimals_cache, hooks::UseEthereumHandle, logs, time, units,
+    EthereumError, LogFilter,
+};
+use std::{collections::HashMap, rc::Rc};
+use web3::{
+    futures::future::{abortable, LocalBoxFuture},
+    types::{Log, H160, H256, U256},
+};
+use yew::{platform::spawn_local, prelude::*};
+
+/// blocks scanned per page when walking back through history
+const PAGE_BLOCKS: u64 = 10_000;
+/// largest block range of a single `eth_getLogs` query
+const LOG_CHUNK_BLOCKS: u64 = 2_000;
+/// pages scanned before giving up on finding `limit` transfers
+const MAX_PAGES: u64 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferDirection {
+    Incoming,
+    Outgoing,
+}
+
+/// An ERC20 transfer to or from the connected account
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transfer {
+    pub token: H160,
+    pub from: H160,
+    pub to: H160,
+    pub value: U256,
+    pub direction: TransferDirection,
+    pub tx_hash: Option<H256>,
+    pub block_number: Option<u64>,
+    /// unix timestamp of the block, if it could be fetched
+    pub timestamp: Option<u64>,
+}
+
+impl Transfer {
+    /// decode a `Transfer` log as seen by `account`. returns None for logs that aren't an ERC20
+    /// transfer involving the account (eg. ERC721 transfers, which index the token id)
+    pub fn from_log(log: &Log, account: &H160) -> Option<Transfer> {
+        if log.topics.len() != 3 || log.topics[0] != logs::transfer_topic() {
+            return None;
+        }
+        let from = abi::decode_address(log.topics[1].as_bytes()).ok()?;
+        let to = abi::decode_address(log.topics[2].as_bytes()).ok()?;
+        let direction = if &to == account {
+            TransferDirection::Incoming
+        } else if &from == account {
+            TransferDirection::Outgoing
+        } else {
+            return None;
+        };
+
+        Some(Transfer {
+            token: log.address,
+            from,
+            to,
+            value: abi::decode_uint(&log.data.0).ok()?,
+            direction,
+            tx_hash: log.transaction_hash,
+            block_number: log.block_number.map(|number| number.as_u64()),
+            timestamp: None,
+        })
+    }
+}
+
+type FetchTransfers =
+    dyn Fn(H160, usize) -> LocalBoxFuture<'static, Result<Vec<Transfer>, EthereumError>>;
+
+/// A custom source of transfers (eg. an indexer api) used by `TransactionHistory` instead of
+/// scanning logs. called with the account and the maximum number of transfers wanted
+#[derive(Clone)]
+pub struct TransferFetcher(pub Rc<FetchTransfers>);
+
+impl PartialEq for TransferFetcher {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// the most recent `limit` transfers of `tokens` to or from `account`, newest first, found by
+/// walking back from the latest block page by page
+pub async fn recent_transfers(
+    ethereum: &UseEthereumHandle,
+    account: H160,
+    tokens: &[H160],
+    limit: usize,
+) -> Result<Vec<Transfer>, EthereumError> {
+    let account_topic = logs::address_topic(&account);
+    let outgoing = LogFilter {
+        address: tokens.to_vec(),
+        topics: vec![
+            Some(vec![logs::transfer_topic()]),
+            Some(vec![account_topic]),
+        ],
+        ..Default::default()
+    };
+    let incoming = LogFilter {
+        topics: vec![
+            Some(vec![logs::transfer_topic()]),
+            None,
+            Some(vec![account_topic]),
+        ],
+        ..outgoing.clone()
+    };
+
+    let mut transfers = Vec::new();
+    let mut to = ethereum.block_number().await?;
+    for _ in 0..MAX_PAGES {
+        let from = to.saturating_sub(PAGE_BLOCKS - 1);
+        for filter in [&outgoing, &incoming] {
+            let page = ethereum
+                .get_logs_chunked(filter, from, to, LOG_CHUNK_BLOCKS)
+                .await?;
+            transfers.extend(
+                page.iter()
+                    .filter_map(|log| Transfer::from_log(log, &account)),
+            );
+        }
+        if transfers.len() >= limit || from == 0 {
+            break;
+        }
+        to = from - 1;
+    }
+
+    transfers.sort_by_key(|transfer| std::cmp::Reverse(transfer.block_number));
+    transfers.truncate(limit);
+
+    let mut timestamps = HashMap::new();
+    for transfer in transfers.iter_mut() {
+        if let Some(number) = transfer.block_number {
+            transfer.timestamp = match timestamps.get(&number) {
+                Some(timestamp) => *timestamp,
+                None => {
+                    let timestamp = ethereum.block_timestamp(number).await?;
+                    timestamps.insert(number, timestamp);
+                    timestamp
+                }
+            };
+        }
+    }
+    Ok(transfers)
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// token contracts to scan for transfers
+    pub tokens: Vec<H160>,
+
+    /// maximum number of transfers listed
+    #[prop_or(10)]
+    pub limit: usize,
+
+    /// fetch transfers from here instead of scanning `eth_getLogs`
+    #[prop_or_default]
+    pub fetcher: Option<TransferFetcher>,
+
+    #[prop_or_default]
+    pub class: Option<String>,
+}
+
+#[function_component]
+pub fn TransactionHistory(props: &Props) -> Html {
+    let ethereum = use_context::<Option<UseEthereumHandle>>().expect(
+        "no ethereum provider found. you must wrap your components in an <EthereumProvider/>",
+    );
+    let decimals_cache = use_erc20_decimals_cache();
+    let transfers = use_state(Vec::<Transfer>::new);
+    let decimals = use_state(HashMap::<H160, u8>::new);
+    let error = use_state(|| None as Option<EthereumError>);
+
+    {
+        let transfers = transfers.clone();
+        let decimals = decimals.clone();
+        let error = error.clone();
+        let handle = ethereum.clone();
+        let fetcher = props.fetcher.clone();
+        let address = ethereum.as_ref().and_then(|e| e.address().cloned());
+        let chain_id = ethereum.as_ref().and_then(UseEthereumHandle::chain_id);
+        use_effect_with_deps(
+            move |(address, _, tokens, limit)| {
+                transfers.set(Vec::new());
+                error.set(None);
+                // aborted when the deps change or the component unmounts, so a slow scan for
+                // the previous account neither keeps paging nor overwrites the new list
+                let abort = match (handle, *address) {
+                    (Some(handle), Some(address)) => {
+                        let tokens = tokens.clone();
+                        let limit = *limit;
+                        let (scan, abort) = abortable(async move {
+                            let result = match fetcher {
+                                Some(fetcher) => (fetcher.0)(address, limit).await,
+                                None => recent_transfers(&handle, address, &tokens, limit).await,
+                            };
+                            match result {
+                                Ok(result) => {
+                                    let mut found = HashMap::new();
+                                    for transfer in result.iter() {
+                                        if let Ok(value) =
+                                            decimals_cache.decimals(&handle, transfer.token).await
+                                        {
+                                            found.insert(transfer.token, value);
+                                        }
+                                    }
+                                    decimals.set(found);
+                                    transfers.set(result);
+                                }
+                                Err(err) => {
+                                    log::error!("failed to fetch transfers: {}", err);
+                                    error.set(Some(err));
+                                }
+                            }
+                        });
+                        spawn_local(async move {
+                            let _ = scan.await;
+                        });
+                        Some(abort)
+                    }
+                    _ => None,
+                };
+                move || {
+                    if let Some(abort) = abort {
+                        abort.abort();
+                    }
+                }
+            },
+            (address, chain_id, props.tokens.clone(), props.limit),
+        );
+    }
+
+    let ethereum = match ethereum {
+        Some(ethereum) => ethereum,
+        None => return html! {},
+    };
+    let explorer = ethereum.chain_id().and_then(chain::from_chain_id);
+    let now = time::now();
+
+    html! {
+        <div class={&props.class}>
+            if let Some(err) = &*error {
+                <div class="error">{err.to_string()}</div>
+            } else if transfers.is_empty() {
+                <div>{"No transfers found"}</div>
+            }
+            <ul>
+                {for transfers.iter().map(|transfer| {
+                    let amount = decimals
+                        .get(&transfer.token)
+                        .map(|decimals| units::format_units_truncated(transfer.value, *decimals as u32, 4))
+                        .unwrap_or_else(|| transfer.value.to_string());
+                    let (label, counterparty) = match transfer.direction {
+                        TransferDirection::Incoming => ("Received", transfer.from),
+                        TransferDirection::Outgoing => ("Sent", transfer.to),
+                    };
+                    let link = explorer
+                        .as_ref()
+                        .zip(transfer.tx_hash.as_ref())
+                        .and_then(|(chain, hash)| chain.explorer_tx_url(hash));
+                    html! {
+                        <li class={classes!("transfer", format!("{:?}", transfer.direction).to_lowercase())}>
+                            {label}{" "}{amount}{" "}<span class="token">{format!("{:?}", transfer.token)}</span>
+                            {if transfer.direction == TransferDirection::Incoming { " from " } else { " to " }}
+                            {format!("{:?}", counterparty)}
+                            if let Some(timestamp) = transfer.timestamp {
+                                {" · "}{time::format_relative_time(timestamp, now)}
+                            }
+                            if let Some(link) = link {
+                                {" "}<a href={link} target="_blank" rel="noopener noreferrer">{"view"}</a>
+                            }
+                        </li>
+                    }
+                })}
+            </ul>
+        </div>
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{
+    hooks::{is_available, UseEthereumHandle},
+    Chain, ConnectButton, SwitchNetworkButton,
+};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// chain the children need the wallet to be on
+    pub required_chain: Chain,
+
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// Renders its children only while an account is connected and the wallet is on
+/// `required_chain`, otherwise a `ConnectButton` or `SwitchNetworkButton` to get there
+#[function_component]
+pub fn Web3Gate(props: &Props) -> Html {
+    let ethereum = use_context::<Option<UseEthereumHandle>>().expect(
+        "no ethereum provider found. you must wrap your components in an <EthereumProvider/>",
+    );
+
+    match ethereum {
+        // the wallet is injected but its node is down, connecting wouldn't help
+        Some(ethereum) if !ethereum.provider_is_connected() => html! {
+            {"RPC unavailable"}
+        },
+        Some(ethereum) if !ethereum.connected() => html! {
+            <ConnectButton />
+        },
+        Some(ethereum) if ethereum.chain_id() != props.required_chain.id() => html! {
+            <SwitchNetworkButton chain={props.required_chain.clone()} />
+        },
+        Some(_) => html! {
+            {for props.children.iter()}
+        },
+        None if !is_available() => html! {
+            <a href="https://metamask.io/download/" target="_blank" rel="noopener noreferrer">
+                {"Install a wallet to continue"}
+            </a>
+        },
+        None => html! {
+            {"No ethereum provider found"}
+        },
+    }
+}
@@ -0,0 +1,50 @@
+//! ENS name resolution. ENS lives on Ethereum mainnet, so the resolution methods of
+//! `UseEthereumHandle` either need the wallet to be on mainnet or a mainnet rpc endpoint set with
+//! `UseEthereumHandle::set_ens_rpc_url`. Otherwise they fail fast with
+//! `EthereumError::WrongNetwork` instead of querying a chain that has no ENS registry.
+use crate::abi;
+use web3::{signing::namehash, types::H160};
+
+/// chain id of Ethereum mainnet, the only chain ENS resolution is attempted on
+pub const ENS_CHAIN_ID: u64 = 1;
+
+/// the ENS registry, at the same address on every network it is deployed to
+pub fn registry() -> H160 {
+    H160::from_slice(&[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x2e, 0x07, 0x4e, 0xc6, 0x9a, 0x0d, 0xfb, 0x29, 0x97,
+        0xba, 0x6c, 0x7d, 0x2e, 0x1e,
+    ])
+}
+
+/// namehash of a name. names are lowercased but otherwise not normalized
+pub fn node(name: &str) -> [u8; 32] {
+    namehash(&name.to_lowercase())
+}
+
+/// name of the reverse record of `address`, `<hex address>.addr.reverse`
+pub fn reverse_name(address: &H160) -> String {
+    format!("{:x}.addr.reverse", address)
+}
+
+/// calldata of `resolver(bytes32)` on the registry
+pub fn resolver_call(node: [u8; 32]) -> Vec<u8> {
+    abi::encode_call(abi::selector("resolver(bytes32)"), &[node])
+}
+
+/// calldata of `addr(bytes32)` on a resolver
+pub fn addr_call(node: [u8; 32]) -> Vec<u8> {
+    abi::encode_call(abi::selector("addr(bytes32)"), &[node])
+}
+
+/// calldata of `name(bytes32)` on a reverse resolver
+pub fn name_call(node: [u8; 32]) -> Vec<u8> {
+    abi::encode_call(abi::selector("name(bytes32)"), &[node])
+}
+
+/// decode an address result, treating the zero address as unset
+pub fn decode_optional_address(data: &[u8]) -> Result<Option<H160>, abi::DecodeError> {
+    if data.is_empty() {
+        return Ok(None);
+    }
+    abi::decode_address(data).map(|address| Some(address).filter(|address| !address.is_zero()))
+}
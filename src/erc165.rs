@@ -0,0 +1,24 @@
+//! [ERC-165](https://eips.ethereum.org/EIPS/eip-165) interface detection, see
+//! `UseEthereumHandle::supports_interface`
+use crate::abi::{self, WORD};
+
+/// ERC-165 itself
+pub const ERC165: [u8; 4] = [0x01, 0xff, 0xc9, 0xa7];
+/// ERC-721 non-fungible tokens
+pub const ERC721: [u8; 4] = [0x80, 0xac, 0x58, 0xcd];
+/// ERC-721 `name`, `symbol` and `tokenURI`
+pub const ERC721_METADATA: [u8; 4] = [0x5b, 0x5e, 0x13, 0x9f];
+/// ERC-721 `totalSupply`, `tokenByIndex` and `tokenOfOwnerByIndex`
+pub const ERC721_ENUMERABLE: [u8; 4] = [0x78, 0x0e, 0x9d, 0x63];
+/// ERC-1155 multi tokens
+pub const ERC1155: [u8; 4] = [0xd9, 0xb6, 0x7a, 0x26];
+/// ERC-1155 `uri`
+pub const ERC1155_METADATA_URI: [u8; 4] = [0x0e, 0x89, 0x34, 0x1c];
+
+/// calldata of `supportsInterface(bytes4)`
+pub fn supports_interface_call(interface_id: [u8; 4]) -> Vec<u8> {
+    // fixed-size bytes are left aligned in their word
+    let mut word = [0u8; WORD];
+    word[..4].copy_from_slice(&interface_id);
+    abi::encode_call(abi::selector("supportsInterface(bytes4)"), &[word])
+}
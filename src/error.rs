@@ -0,0 +1,148 @@
+use crate::{
+    abi::{self, DecodeError},
+    codes, BlockTag,
+};
+use std::fmt;
+
+/// Errors returned by the fallible methods of `UseEthereumHandle`
+#[derive(Debug, Clone)]
+pub enum EthereumError {
+    /// the user rejected the request in their wallet (EIP-1193 code 4001)
+    UserRejected,
+    /// there is no wallet, nor an rpc endpoint in read-only mode, to send requests to
+    NoProvider,
+    /// the wallet doesn't know the chain it was asked to switch to (EIP-1193 code 4902), add it
+    /// with `add_chain` first
+    ChainNotAdded,
+    /// the provider does not support the requested method
+    Unsupported(String),
+    /// the user declined to connect their wallet
+    ConnectRejected,
+    /// the user declined to add or switch to the chain
+    ChainSwitchRejected(u64),
+    /// the method needs a connected account but there is none
+    NotConnected,
+    /// the method only works on chain `expected` but the wallet is on `actual`
+    WrongNetwork { expected: u64, actual: Option<u64> },
+    /// the transaction was mined but reverted
+    TransactionReverted(web3::types::H256),
+    /// the call or gas estimate reverted. `reason` is the `require` message or panic
+    /// description decoded from the revert data, if there was any
+    Revert { reason: Option<String> },
+    /// the provider can't serve state of a past block, common for injected wallets whose nodes
+    /// are not archival
+    ArchivalUnsupported(BlockTag),
+    /// the request was malformed and not sent to the provider
+    InvalidRequest(String),
+    /// the provider answered with a json-rpc error
+    Rpc { code: i64, message: String },
+    /// the response could not be decoded into the expected type
+    Decode(DecodeError),
+    /// any other failure of the underlying transport
+    Transport(web3::Error),
+}
+
+impl fmt::Display for EthereumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EthereumError::UserRejected => write!(f, "request rejected by the user"),
+            EthereumError::NoProvider => write!(f, "no ethereum provider found"),
+            EthereumError::ChainNotAdded => write!(f, "the chain has not been added to the wallet"),
+            EthereumError::Unsupported(method) => {
+                write!(f, "{} is not supported by the provider", method)
+            }
+            EthereumError::ConnectRejected => write!(f, "wallet connection rejected by the user"),
+            EthereumError::ChainSwitchRejected(chain_id) => {
+                write!(f, "switching to chain {} rejected by the user", chain_id)
+            }
+            EthereumError::NotConnected => write!(f, "no account connected"),
+            EthereumError::WrongNetwork {
+                expected,
+                actual: Some(actual),
+            } => {
+                write!(
+                    f,
+                    "expected chain {} but connected to chain {}",
+                    expected, actual
+                )
+            }
+            EthereumError::WrongNetwork {
+                expected,
+                actual: None,
+            } => {
+                write!(f, "expected chain {} but the chain is unknown", expected)
+            }
+            EthereumError::TransactionReverted(hash) => {
+                write!(f, "transaction {:?} reverted", hash)
+            }
+            EthereumError::Revert {
+                reason: Some(reason),
+            } => write!(f, "execution reverted: {}", reason),
+            EthereumError::Revert { reason: None } => write!(f, "execution reverted"),
+            EthereumError::ArchivalUnsupported(block) => {
+                write!(f, "the provider can't serve state at block {:?}", block)
+            }
+            EthereumError::InvalidRequest(reason) => write!(f, "invalid request: {}", reason),
+            EthereumError::Rpc { code, message } => write!(f, "rpc error {}: {}", code, message),
+            EthereumError::Decode(err) => write!(f, "decode error: {}", err),
+            EthereumError::Transport(err) => write!(f, "transport error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for EthereumError {}
+
+impl From<web3::Error> for EthereumError {
+    fn from(err: web3::Error) -> Self {
+        match err {
+            web3::Error::Rpc(err) => match err.code.code() {
+                codes::USER_REJECTED => EthereumError::UserRejected,
+                codes::UNRECOGNIZED_CHAIN => EthereumError::ChainNotAdded,
+                codes::UNSUPPORTED_METHOD
+                | codes::METHOD_NOT_FOUND
+                | codes::METHOD_NOT_SUPPORTED => EthereumError::Unsupported(err.message),
+                code => {
+                    let reason = revert_data(err.data.as_ref())
+                        .and_then(|data| abi::decode_revert_reason(&data));
+                    // metamask reports reverts as internal errors, recognizable by the message
+                    if reason.is_some()
+                        || code == codes::EXECUTION_REVERTED
+                        || err.message.contains("execution reverted")
+                    {
+                        EthereumError::Revert { reason }
+                    } else {
+                        EthereumError::Rpc {
+                            code,
+                            message: err.message,
+                        }
+                    }
+                }
+            },
+            err => EthereumError::Transport(err),
+        }
+    }
+}
+
+/// the revert payload in the `data` of an rpc error. nodes put the hex string there directly,
+/// metamask nests it in `data.data` or `data.originalError.data`
+fn revert_data(data: Option<&serde_json::Value>) -> Option<Vec<u8>> {
+    let data = data?;
+    [
+        Some(data),
+        data.get("data"),
+        data.get("originalError")
+            .and_then(|original| original.get("data")),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(serde_json::Value::as_str)
+    .find_map(|hex| web3::helpers::decode::<web3::types::Bytes>(hex.into()).ok())
+    .filter(|bytes| bytes.0.len() >= 4)
+    .map(|bytes| bytes.0)
+}
+
+impl From<DecodeError> for EthereumError {
+    fn from(err: DecodeError) -> Self {
+        EthereumError::Decode(err)
+    }
+}
@@ -0,0 +1,209 @@
+use serde::Deserialize;
+use web3::types::{U256, U64};
+
+/// blocks of `eth_feeHistory` fee suggestions are based on
+pub const FEE_HISTORY_BLOCKS: u64 = 20;
+/// assumed block time for chains whose recent blocks can't be read
+pub const DEFAULT_BLOCK_TIME_SECS: f64 = 12.0;
+
+/// How urgently a transaction should be included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FeeLevel {
+    Slow,
+    #[default]
+    Standard,
+    Fast,
+}
+
+impl FeeLevel {
+    /// percentile of the priority fees paid in recent blocks this level matches
+    pub fn reward_percentile(&self) -> f64 {
+        match self {
+            FeeLevel::Slow => 10.0,
+            FeeLevel::Standard => 50.0,
+            FeeLevel::Fast => 90.0,
+        }
+    }
+
+    /// blocks a transaction paying this level's tip typically waits while blocks are full
+    pub fn expected_blocks(&self) -> u32 {
+        match self {
+            FeeLevel::Slow => 5,
+            FeeLevel::Standard => 2,
+            FeeLevel::Fast => 1,
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            FeeLevel::Slow => 0,
+            FeeLevel::Standard => 1,
+            FeeLevel::Fast => 2,
+        }
+    }
+}
+
+/// EIP-1559 fees for a `FeeLevel`, ready for a `TransactionRequest`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeSuggestion {
+    /// base fee of the next block
+    pub base_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    /// twice the base fee plus the tip, so the transaction stays valid through several blocks
+    /// of rising base fees
+    pub max_fee_per_gas: U256,
+}
+
+/// An `eth_feeHistory` response, for custom fee strategies and charts
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeHistory {
+    /// number of the first block of the history
+    pub oldest_block: U64,
+    /// base fee of each block, plus one for the block after the newest
+    pub base_fee_per_gas: Vec<U256>,
+    /// gas used by each block as a fraction of its gas limit
+    pub gas_used_ratio: Vec<f64>,
+    /// the priority fee paid at each requested percentile, per block. empty when no
+    /// percentiles were requested, as nodes then leave it out
+    #[serde(default)]
+    pub reward: Vec<Vec<U256>>,
+}
+
+/// the part of an `eth_feeHistory` response fee suggestions need
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RawFeeHistory {
+    pub base_fee_per_gas: Vec<U256>,
+    pub gas_used_ratio: Vec<f64>,
+    #[serde(default)]
+    pub reward: Vec<Vec<U256>>,
+}
+
+impl RawFeeHistory {
+    /// reward percentiles to request so `suggest` can serve every level
+    pub fn percentiles() -> [f64; 3] {
+        [
+            FeeLevel::Slow.reward_percentile(),
+            FeeLevel::Standard.reward_percentile(),
+            FeeLevel::Fast.reward_percentile(),
+        ]
+    }
+
+    /// fees for `level` from a history fetched with `percentiles()`: the mean of the tips paid
+    /// at the level's percentile over the history
+    pub fn suggest(&self, level: FeeLevel) -> FeeSuggestion {
+        // the last entry is the base fee of the block after the newest one
+        let base_fee_per_gas = self.base_fee_per_gas.last().copied().unwrap_or_default();
+        let tips: Vec<U256> = self
+            .reward
+            .iter()
+            .filter_map(|rewards| rewards.get(level.index()).copied())
+            .collect();
+        let max_priority_fee_per_gas = if tips.is_empty() {
+            U256::zero()
+        } else {
+            tips.iter()
+                .fold(U256::zero(), |sum, tip| sum.saturating_add(*tip))
+                / U256::from(tips.len())
+        };
+        FeeSuggestion {
+            base_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_fee_per_gas: base_fee_per_gas
+                .saturating_mul(U256::from(2))
+                .saturating_add(max_priority_fee_per_gas),
+        }
+    }
+
+    /// whether recent blocks were more than half full, the EIP-1559 target, so tips decide
+    /// which transactions get in
+    pub fn congested(&self) -> bool {
+        if self.gas_used_ratio.is_empty() {
+            return false;
+        }
+        let mean = self.gas_used_ratio.iter().sum::<f64>() / self.gas_used_ratio.len() as f64;
+        mean > 0.5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    const GWEI: u64 = 1_000_000_000;
+
+    /// two blocks of history requested with `RawFeeHistory::percentiles()`
+    fn response() -> Value {
+        json!({
+            "oldestBlock": "0x10b52f1",
+            "baseFeePerGas": ["0x3fa63a3f7", "0x3d6ee3c9c", "0x3b1f8e0e2"],
+            "gasUsedRatio": [0.3, 0.2],
+            "reward": [
+                ["0x59682f00", "0x9502f900", "0xb2d05e00"],
+                ["0x3b9aca00", "0x77359400", "0xee6b2800"]
+            ]
+        })
+    }
+
+    #[test]
+    fn decodes_fee_history() {
+        let history: FeeHistory = serde_json::from_value(response()).unwrap();
+        assert_eq!(history.oldest_block, U64::from(0x10b52f1));
+        assert_eq!(
+            history.base_fee_per_gas,
+            vec![
+                U256::from(0x3fa63a3f7u64),
+                U256::from(0x3d6ee3c9cu64),
+                U256::from(0x3b1f8e0e2u64)
+            ]
+        );
+        assert_eq!(history.gas_used_ratio, vec![0.3, 0.2]);
+        assert_eq!(
+            history.reward,
+            vec![
+                vec![
+                    U256::from(1_500_000_000),
+                    U256::from(2_500_000_000u64),
+                    U256::from(3 * GWEI)
+                ],
+                vec![U256::from(GWEI), U256::from(2 * GWEI), U256::from(4 * GWEI)],
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_fee_history_without_reward() {
+        let mut response = response();
+        response.as_object_mut().unwrap().remove("reward");
+        let history: FeeHistory = serde_json::from_value(response.clone()).unwrap();
+        assert!(history.reward.is_empty());
+        let raw: RawFeeHistory = serde_json::from_value(response).unwrap();
+        assert_eq!(
+            raw.suggest(FeeLevel::Fast).max_priority_fee_per_gas,
+            U256::zero()
+        );
+    }
+
+    #[test]
+    fn suggests_fees_from_the_history() {
+        let raw: RawFeeHistory = serde_json::from_value(response()).unwrap();
+        let base_fee_per_gas = U256::from(0x3b1f8e0e2u64);
+        let standard = raw.suggest(FeeLevel::Standard);
+        assert_eq!(standard.base_fee_per_gas, base_fee_per_gas);
+        assert_eq!(
+            standard.max_priority_fee_per_gas,
+            U256::from(2_250_000_000u64)
+        );
+        assert_eq!(
+            standard.max_fee_per_gas,
+            base_fee_per_gas * U256::from(2) + U256::from(2_250_000_000u64)
+        );
+        assert_eq!(
+            raw.suggest(FeeLevel::Slow).max_priority_fee_per_gas,
+            U256::from(1_250_000_000)
+        );
+        assert!(!raw.congested());
+    }
+}
@@ -0,0 +1,23 @@
+use crate::EthereumError;
+use std::fmt;
+use web3::{
+    futures::future::{LocalBoxFuture, Shared},
+    types::H160,
+};
+
+pub(crate) type ConnectionFlow = Shared<LocalBoxFuture<'static, Result<Vec<H160>, EthereumError>>>;
+
+/// The connection flow of a `UseEthereumHandle` that is currently running, so a second
+/// `connect()` joins it instead of prompting the user again
+#[derive(Default)]
+pub(crate) struct PendingConnection {
+    pub flow: Option<ConnectionFlow>,
+}
+
+impl fmt::Debug for PendingConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PendingConnection")
+            .field("pending", &self.flow.is_some())
+            .finish()
+    }
+}
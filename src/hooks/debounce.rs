@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+
+/// default window in which bursts of wallet events are coalesced
+pub const DEFAULT_EVENT_DEBOUNCE_MS: u32 = 50;
+
+/// Shared bookkeeping for debouncing the wallet events of a `UseEthereumHandle`
+#[derive(Debug)]
+pub(crate) struct EventDebounce {
+    /// events of the same kind arriving within this many milliseconds are coalesced, only the
+    /// last one is applied. 0 applies every event immediately
+    pub window_ms: u32,
+    /// bumped on every event of a kind, a delayed update only applies if it is still the latest
+    generations: HashMap<&'static str, u32>,
+}
+
+impl Default for EventDebounce {
+    fn default() -> Self {
+        EventDebounce {
+            window_ms: DEFAULT_EVENT_DEBOUNCE_MS,
+            generations: HashMap::new(),
+        }
+    }
+}
+
+impl EventDebounce {
+    /// record a new `event`, returning its generation
+    pub fn bump(&mut self, event: &'static str) -> u32 {
+        let generation = self.generations.entry(event).or_insert(0);
+        *generation = generation.wrapping_add(1);
+        *generation
+    }
+
+    /// whether no `event` arrived after the one of `generation`
+    pub fn is_latest(&self, event: &'static str, generation: u32) -> bool {
+        self.generations.get(event) == Some(&generation)
+    }
+}
@@ -0,0 +1,31 @@
+use yew::Callback;
+
+/// Shared bookkeeping for the idle-disconnect policy of a `UseEthereumHandle`
+#[derive(Debug, Default)]
+pub(crate) struct IdleState {
+    /// disconnect after this many milliseconds without requests
+    pub timeout_ms: Option<u32>,
+    /// `Date.now()` of the last request
+    pub last_activity: f64,
+    /// whether there is a connection the policy may drop
+    pub armed: bool,
+    /// bumped to stop the running watcher, when the timeout changes or the hook unmounts
+    pub generation: u32,
+    pub on_idle: Option<Callback<()>>,
+}
+
+impl IdleState {
+    pub fn touch(&mut self) {
+        self.last_activity = js_sys::Date::now();
+    }
+
+    /// milliseconds until the session counts as idle, `None` if the watcher of `generation`
+    /// should stop
+    pub fn remaining(&self, generation: u32) -> Option<f64> {
+        if self.generation != generation {
+            return None;
+        }
+        let timeout = self.timeout_ms? as f64;
+        Some(timeout - (js_sys::Date::now() - self.last_activity))
+    }
+}
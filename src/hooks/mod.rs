@@ -1,2 +1,32 @@
+mod connecting;
+mod debounce;
+mod idle;
+mod read_rpc;
+mod subscription;
+mod use_async_action;
+mod use_balance;
+mod use_balance_cache;
+mod use_block_number;
+mod use_contract_read;
+mod use_erc20_decimals_cache;
 mod use_ethereum;
+mod use_fee_data;
+mod use_gas_price;
+mod use_poll;
+mod use_require_chain;
+mod use_transaction;
+mod use_wallet_info;
+pub use debounce::DEFAULT_EVENT_DEBOUNCE_MS;
+pub use use_async_action::*;
+pub use use_balance::*;
+pub use use_balance_cache::*;
+pub use use_block_number::*;
+pub use use_contract_read::*;
+pub use use_erc20_decimals_cache::*;
 pub use use_ethereum::*;
+pub use use_fee_data::*;
+pub use use_gas_price::*;
+pub use use_poll::*;
+pub use use_require_chain::*;
+pub use use_transaction::*;
+pub use use_wallet_info::*;
@@ -0,0 +1,20 @@
+use std::{cell::Cell, rc::Rc};
+use web3::transports::Http;
+
+/// The endpoint set with `set_read_rpc_url`, along with the chain it serves
+#[derive(Debug, Clone)]
+pub(crate) struct ReadRpc {
+    pub rpc: Http,
+    /// `eth_chainId` of `rpc`, `None` until first asked. shared by the clones so it is only
+    /// asked once
+    pub chain_id: Rc<Cell<Option<u64>>>,
+}
+
+impl ReadRpc {
+    pub fn new(rpc: Http) -> ReadRpc {
+        ReadRpc {
+            rpc,
+            chain_id: Rc::default(),
+        }
+    }
+}
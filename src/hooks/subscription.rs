@@ -0,0 +1,33 @@
+use super::UseEthereumHandle;
+use serde_json::Value;
+use web3::futures::future::AbortHandle;
+use yew::platform::spawn_local;
+
+/// A wallet event loop spawned by a `UseEthereumHandle`, aborted when dropped
+#[derive(Debug)]
+pub(crate) struct Subscription(pub AbortHandle);
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// An `eth_subscribe` subscription, cancelled with `eth_unsubscribe` when dropped. dropping the
+/// notification stream alone only stops listening, the wallet would keep sending them
+pub(crate) struct WalletSubscription {
+    pub ethereum: UseEthereumHandle,
+    pub id: Value,
+}
+
+impl Drop for WalletSubscription {
+    fn drop(&mut self) {
+        let ethereum = self.ethereum.clone();
+        let id = self.id.take();
+        spawn_local(async move {
+            if let Err(err) = ethereum.request("eth_unsubscribe", vec![id]).await {
+                log::warn!("eth_unsubscribe failed: {}", err);
+            }
+        });
+    }
+}
@@ -0,0 +1,74 @@
+use std::{future::Future, rc::Rc};
+use yew::{platform::spawn_local, prelude::*};
+
+/// State of an async action created with `use_async_action`
+#[derive(Clone, PartialEq)]
+pub struct UseAsyncActionHandle<T, E> {
+    /// start the action. ignored while a previous run is still pending
+    pub run: Callback<()>,
+    pub pending: bool,
+    /// error of the last run, cleared when a new run starts
+    pub error: Option<E>,
+    /// result of the last successful run
+    pub result: Option<T>,
+}
+
+/// wraps a future-returning closure into a `run` callback, tracking whether it is pending and
+/// what it last returned. the closure of the latest render is the one invoked
+#[hook]
+pub fn use_async_action<T, E, F, Fut>(action: F) -> UseAsyncActionHandle<T, E>
+where
+    T: Clone + 'static,
+    E: Clone + 'static,
+    F: Fn() -> Fut + 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+{
+    let pending = use_state(|| false);
+    let error = use_state(|| None as Option<E>);
+    let result = use_state(|| None as Option<T>);
+    // a ref rather than the state so two clicks in the same frame don't both start a run
+    let running = use_mut_ref(|| false);
+    let action = {
+        let action_ref = use_mut_ref(|| None as Option<Rc<F>>);
+        *action_ref.borrow_mut() = Some(Rc::new(action));
+        action_ref
+    };
+
+    let run = {
+        let pending = pending.clone();
+        let error = error.clone();
+        let result = result.clone();
+        Callback::from(move |_| {
+            if *running.borrow() {
+                return;
+            }
+            let action = match action.borrow().clone() {
+                Some(action) => action,
+                None => return,
+            };
+            *running.borrow_mut() = true;
+            pending.set(true);
+            error.set(None);
+
+            let running = running.clone();
+            let pending = pending.clone();
+            let error = error.clone();
+            let result = result.clone();
+            spawn_local(async move {
+                match action().await {
+                    Ok(value) => result.set(Some(value)),
+                    Err(err) => error.set(Some(err)),
+                }
+                *running.borrow_mut() = false;
+                pending.set(false);
+            });
+        })
+    };
+
+    UseAsyncActionHandle {
+        run,
+        pending: *pending,
+        error: (*error).clone(),
+        result: (*result).clone(),
+    }
+}
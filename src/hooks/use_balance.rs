@@ -0,0 +1,62 @@
+use super::{use_balance_cache, use_poll_with_refetch, UseEthereumHandle};
+use crate::{EthereumError, DEFAULT_BLOCK_TIME_SECS};
+use web3::types::U256;
+use yew::prelude::*;
+
+/// State of the balance read by `use_balance`
+#[derive(Clone, PartialEq)]
+pub struct UseBalanceHandle {
+    /// native currency balance of the connected account in wei, `None` until read
+    pub balance: Option<U256>,
+    /// read the balance again right away, eg. once a transaction is confirmed. the current
+    /// balance is kept until the new one arrives
+    pub refetch: Callback<()>,
+}
+
+/// native currency balance of the connected account, read again about every block and
+/// whenever the account or chain changes. under a `BalanceCacheProvider` the reads go through
+/// its `BalanceCache`, shared with every other consumer of the same balance
+#[hook]
+pub fn use_balance(ethereum: Option<UseEthereumHandle>) -> UseBalanceHandle {
+    let address = ethereum.as_ref().and_then(|e| e.address().cloned());
+    let chain_id = ethereum.as_ref().and_then(UseEthereumHandle::chain_id);
+    let cache = use_balance_cache();
+
+    let poll = {
+        let cache = cache.clone();
+        use_poll_with_refetch(
+            move || {
+                let ethereum = ethereum.clone();
+                let cache = cache.clone();
+                async move {
+                    match (ethereum, address, cache) {
+                        (Some(ethereum), Some(address), Some(cache)) => {
+                            cache.balance(&ethereum, address, None).await.map(Some)
+                        }
+                        (Some(ethereum), Some(address), None) => {
+                            ethereum.get_balance(address).await.map(Some)
+                        }
+                        _ => Ok::<_, EthereumError>(None),
+                    }
+                }
+            },
+            Some((DEFAULT_BLOCK_TIME_SECS * 1000.0) as u32),
+            (address, chain_id),
+        )
+    };
+
+    let refetch = {
+        let refetch = poll.refetch.clone();
+        Callback::from(move |_| {
+            // a cached balance would be served again rather than read
+            if let (Some(cache), Some(address)) = (&cache, address) {
+                cache.invalidate(address);
+            }
+            refetch.emit(());
+        })
+    };
+    UseBalanceHandle {
+        balance: poll.value.flatten(),
+        refetch,
+    }
+}
@@ -0,0 +1,8 @@
+use crate::BalanceCache;
+use yew::prelude::*;
+
+/// the balance cache of the enclosing `BalanceCacheProvider`, `None` outside of one
+#[hook]
+pub fn use_balance_cache() -> Option<BalanceCache> {
+    use_context::<BalanceCache>()
+}
@@ -0,0 +1,71 @@
+use super::{use_poll, UseEthereumHandle};
+use crate::{EthereumError, DEFAULT_BLOCK_TIME_SECS};
+use web3::futures::future::abortable;
+use yew::{platform::spawn_local, prelude::*};
+
+/// number of the latest block, following `newHeads` notifications. providers that can't
+/// subscribe are polled with `eth_blockNumber` about once a block instead. `None` until the
+/// first block is known
+#[hook]
+pub fn use_block_number(ethereum: Option<UseEthereumHandle>) -> Option<u64> {
+    let headed = use_state_eq(|| None as Option<u64>);
+    let polling = use_state_eq(|| false);
+    let chain_id = ethereum.as_ref().and_then(UseEthereumHandle::chain_id);
+    let available = ethereum.is_some();
+
+    {
+        let headed = headed.clone();
+        let polling = polling.clone();
+        let ethereum = ethereum.clone();
+        use_effect_with_deps(
+            move |_| {
+                headed.set(None);
+                polling.set(false);
+                let abort = ethereum.map(|ethereum| {
+                    let (follow, abort) = abortable(async move {
+                        let subscribed = ethereum
+                            .on_new_heads(move |header| {
+                                if let Some(number) = header.number {
+                                    headed.set(Some(number.as_u64()));
+                                }
+                            })
+                            .await;
+                        if let Err(err) = subscribed {
+                            log::info!("newHeads unavailable, polling eth_blockNumber: {}", err);
+                        }
+                        polling.set(true);
+                    });
+                    spawn_local(async move {
+                        let _ = follow.await;
+                    });
+                    abort
+                });
+                move || {
+                    if let Some(abort) = abort {
+                        abort.abort();
+                    }
+                }
+            },
+            (chain_id, available),
+        );
+    }
+
+    // the next header may only arrive after a full block time, so the number is read right
+    // away, then about once a block when there are no headers to follow
+    let polled = use_poll(
+        move || {
+            let ethereum = ethereum.clone();
+            async move {
+                match ethereum {
+                    Some(ethereum) => ethereum.block_number().await.map(Some),
+                    None => Ok::<_, EthereumError>(None),
+                }
+            }
+        },
+        polling.then(|| (DEFAULT_BLOCK_TIME_SECS * 1000.0) as u32),
+        (chain_id, available, *polling),
+    )
+    .flatten();
+
+    (*headed).max(polled)
+}
@@ -0,0 +1,68 @@
+use super::UseEthereumHandle;
+use crate::EthereumError;
+use std::{cell::Cell, rc::Rc};
+use web3::types::H160;
+use yew::{platform::spawn_local, prelude::*};
+
+/// State of the `eth_call` made by `use_contract_read`
+#[derive(Clone)]
+pub struct UseContractReadHandle {
+    /// raw return data of the call, kept while a refetch is in flight
+    pub data: Option<Vec<u8>>,
+    pub loading: bool,
+    /// error of the last call, cleared when a new call starts
+    pub error: Option<EthereumError>,
+}
+
+/// `eth_call` of `data` against `to`, made again whenever the inputs or the connected chain
+/// change. pass the result of `use_block_number` as `block_number` to also refetch on every
+/// new block, or `None` to read once. decoding the returned bytes is left to the caller
+#[hook]
+pub fn use_contract_read(
+    ethereum: Option<UseEthereumHandle>,
+    to: H160,
+    data: Vec<u8>,
+    block_number: Option<u64>,
+) -> UseContractReadHandle {
+    let chain_id = ethereum.as_ref().and_then(UseEthereumHandle::chain_id);
+    let result = use_state(|| None as Option<Vec<u8>>);
+    let loading = use_state(|| false);
+    let error = use_state(|| None as Option<EthereumError>);
+
+    {
+        let result = result.clone();
+        let loading = loading.clone();
+        let error = error.clone();
+        use_effect_with_deps(
+            move |(to, data, _, _)| {
+                // cleared when the deps change or the component unmounts, dropping the result
+                let active = Rc::new(Cell::new(true));
+                loading.set(ethereum.is_some());
+                error.set(None);
+                if let Some(ethereum) = ethereum {
+                    let active = active.clone();
+                    let (to, data) = (*to, data.clone());
+                    spawn_local(async move {
+                        let read = ethereum.call(to, data.into()).await;
+                        if !active.get() {
+                            return;
+                        }
+                        match read {
+                            Ok(bytes) => result.set(Some(bytes.0)),
+                            Err(err) => error.set(Some(err)),
+                        }
+                        loading.set(false);
+                    });
+                }
+                move || active.set(false)
+            },
+            (to, data, chain_id, block_number),
+        );
+    }
+
+    UseContractReadHandle {
+        data: (*result).clone(),
+        loading: *loading,
+        error: (*error).clone(),
+    }
+}
@@ -0,0 +1,10 @@
+use crate::Erc20DecimalsCache;
+use yew::prelude::*;
+
+/// the app-wide ERC20 decimals cache provided by `EthereumContextProvider`. outside of a provider
+/// a cache local to the calling component is returned instead
+#[hook]
+pub fn use_erc20_decimals_cache() -> Erc20DecimalsCache {
+    let local = use_state(Erc20DecimalsCache::default);
+    use_context::<Erc20DecimalsCache>().unwrap_or_else(|| (*local).clone())
+}
@@ -1,111 +1,617 @@
-use crate::{Chain, ERC20Asset};
+use super::{
+    connecting::PendingConnection,
+    debounce::EventDebounce,
+    idle::IdleState,
+    read_rpc::ReadRpc,
+    subscription::{Subscription, WalletSubscription},
+};
+#[cfg(feature = "mock")]
+use crate::MockProvider;
+use crate::{
+    abi::{self, DecodeError},
+    chain::{self, ChainWarning},
+    codes, ens, erc165, js_error_to_string,
+    multicall::{self, Call3},
+    storage, units, AddChainOutcome, AddressStyle, BaseCurrency, BlockTag, Chain, ERC20Asset,
+    Eip712Domain, EthereumError, FeeHistory, FeeLevel, FeeSuggestion, LogFilter, Permit,
+    PermitSignature, PollConfig, RawFeeHistory, Siwe, SiweSignature, TransactionFees,
+    TransactionReceipt, TransactionRequest, TxOutcome, TypedData, UnsignedTransaction, WalletInfo,
+    DEFAULT_BLOCK_TIME_SECS, FEE_HISTORY_BLOCKS,
+};
+use crate::{EventListener, JsListeners, Middleware, RequestMiddleware, ResponseMiddleware};
+use js_sys::Reflect;
 use serde_json::json;
-use wasm_bindgen::JsValue;
+use std::{cell::RefCell, collections::HashMap, future::Future, rc::Rc, time::Duration};
+use wasm_bindgen::{JsCast, JsValue};
 use web3::{
-    futures::StreamExt,
-    transports::eip_1193::{Eip1193, Provider},
-    types::{H160, U256},
+    futures::{
+        channel::mpsc,
+        future::{abortable, join_all},
+        FutureExt, StreamExt,
+    },
+    transports::{
+        eip_1193::{Eip1193, Provider},
+        Http,
+    },
+    types::{Block, BlockHeader, Bytes, CallRequest, Log, H160, H256, U256, U64},
     Transport,
 };
-use yew::{platform::spawn_local, prelude::*};
+use yew::{
+    platform::{spawn_local, time::sleep},
+    prelude::*,
+};
+
+/// Where the connection of a `UseEthereumHandle` stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    Disconnected,
+    /// `connect()` is waiting for the user to answer the wallet prompt
+    Connecting,
+    Connected,
+    /// not connected and a flow failed, see `last_error`
+    Errored,
+}
 
 #[derive(Clone, Debug)]
 pub struct UseEthereumHandle {
-    pub provider: Provider,
+    /// the injected wallet, `None` in read-only mode
+    pub provider: Option<Provider>,
+    /// transport over `provider`, created once and shared by every request and event stream
+    transport: Option<Eip1193>,
+    /// plain json-rpc endpoint used when there is no wallet
+    rpc: Option<Http>,
     connected: UseStateHandle<bool>,
     accounts: UseStateHandle<Option<Vec<H160>>>,
+    /// index in `accounts` of the account `address()` returns
+    selected_account: UseStateHandle<usize>,
     chain_id: UseStateHandle<Option<U256>>,
+    /// whether `connect()` is waiting on the wallet, see `status`
+    connect_pending: UseStateHandle<bool>,
+    /// the last failure of a background flow, see `last_error`
+    last_error: UseStateHandle<Option<EthereumError>>,
+    idle: Rc<RefCell<IdleState>>,
+    /// the `eth_requestAccounts` flow in progress, joined by concurrent `connect()` calls
+    connecting: Rc<RefCell<PendingConnection>>,
+    /// whether connections are remembered across page loads, see `set_persist_connection`
+    persist: Rc<RefCell<bool>>,
+    js_listeners: Rc<RefCell<JsListeners>>,
+    /// the wallet event loops started by `connect()`, aborted when dropped
+    subscriptions: Rc<RefCell<Vec<Subscription>>>,
+    middleware: Rc<RefCell<Middleware>>,
+    debounce: Rc<RefCell<EventDebounce>>,
+    /// endpoint serving the reads `is_read_method` picks out instead of the wallet
+    read_rpc: Rc<RefCell<Option<ReadRpc>>>,
+    /// mainnet endpoint used for ENS resolution regardless of the wallet's chain
+    ens_rpc: Rc<RefCell<Option<Http>>>,
+    /// names resolved so far, shared by `resolve_ens` and `resolve_ens_batch`
+    ens_cache: Rc<RefCell<HashMap<String, Option<H160>>>>,
+    /// primary names found by `lookup_ens`, used by `AddressStyle::EnsOrShort`
+    ens_names: Rc<RefCell<HashMap<H160, Option<String>>>>,
 }
 
 impl PartialEq for UseEthereumHandle {
     fn eq(&self, other: &Self) -> bool {
         self.connected == other.connected
             && self.accounts == other.accounts
+            && self.selected_account == other.selected_account
             && self.chain_id == other.chain_id
+            && self.connect_pending == other.connect_pending
+            // `web3::Error` can't be compared, the message is what gets rendered anyway
+            && self.last_error.as_ref().map(ToString::to_string)
+                == other.last_error.as_ref().map(ToString::to_string)
     }
 }
 
 impl UseEthereumHandle {
-    pub async fn connect(&self) -> Result<(), String> {
+    /// prompt the wallet for its accounts. fails with `UserRejected` if the user dismisses the
+    /// prompt, the error is also kept as `last_error`
+    pub async fn connect(&self) -> Result<(), EthereumError> {
         log::info!("connect()");
-        let web3 = web3::Web3::new(Eip1193::new(self.provider.clone()));
+        if self.is_read_only() {
+            return Err(EthereumError::NoProvider);
+        }
+        self.connect_pending.set(true);
+        let result = self.request_connection().await;
+        self.connect_pending.set(false);
+        if let Err(err) = result {
+            log::error!("connect failed: {}", err);
+            self.report_error(err.clone());
+            return Err(err);
+        }
+        Ok(())
+    }
 
-        if let Ok(addresses) = web3.eth().request_accounts().await {
-            log::info!("request_accounts() {:?}", addresses);
+    /// read-only handles have an rpc endpoint in place of a wallet, so there is nothing to
+    /// connect to
+    fn is_read_only(&self) -> bool {
+        self.transport.is_none() && self.rpc.is_some()
+    }
 
-            self.connected.set(true);
-            self.accounts.set(Some(addresses));
+    /// the last error of a flow that has no caller to return it to, such as `connect()` or a
+    /// wallet event that couldn't be understood. `EthereumErrorBoundary` renders it
+    pub fn last_error(&self) -> Option<EthereumError> {
+        (*self.last_error).clone()
+    }
 
-            self.chain_id.set(web3.eth().chain_id().await.ok());
+    /// forget `last_error`, eg. before retrying
+    pub fn clear_error(&self) {
+        self.last_error.set(None);
+    }
 
-            {
-                let this = self.clone();
-                spawn_local(async move {
-                    let this = this.clone();
-                    this.on_chain_changed(|chain_id| {
-                        // chain_id is a decimal string
-                        log::info!("event: chainChanged {:?}", chain_id);
-                        this.chain_id.set(Some(
-                            U256::from_dec_str(&chain_id)
-                                .expect(&format!("chain_id should be a valid U256 {}", &chain_id)),
-                        ));
-                    })
-                    .await;
-                });
+    fn report_error(&self, err: EthereumError) {
+        self.last_error.set(Some(err));
+    }
+
+    /// `connect()` and make sure the wallet is on `chain`, adding the chain to the wallet or
+    /// switching to it as needed. the user declining the connection fails with
+    /// `ConnectRejected`, declining to add or switch the chain with `ChainSwitchRejected`
+    pub async fn connect_to(&self, chain: &Chain) -> Result<(), EthereumError> {
+        let expected = chain.id().ok_or_else(|| {
+            EthereumError::InvalidRequest(format!("invalid chain id {}", chain.chain_id))
+        })?;
+        match self.connect().await {
+            Err(EthereumError::UserRejected) => return Err(EthereumError::ConnectRejected),
+            Err(err) => return Err(err),
+            Ok(_) => (),
+        }
+        if self.current_chain_id().await? == expected {
+            return Ok(());
+        }
+
+        if self.add_chain(chain).await? == AddChainOutcome::Rejected {
+            return Err(EthereumError::ChainSwitchRejected(expected));
+        }
+        // wallets usually offer to switch right after adding a chain
+        if self.current_chain_id().await? == expected {
+            return Ok(());
+        }
+
+        match self
+            .request(
+                "wallet_switchEthereumChain",
+                vec![json!({ "chainId": chain.chain_id })],
+            )
+            .await
+        {
+            Err(EthereumError::UserRejected) => {
+                return Err(EthereumError::ChainSwitchRejected(expected))
             }
+            Err(err) => return Err(err),
+            Ok(_) => (),
+        }
+        let actual = self.current_chain_id().await?;
+        if actual != expected {
+            return Err(EthereumError::WrongNetwork {
+                expected,
+                actual: Some(actual),
+            });
+        }
+        Ok(())
+    }
 
-            {
-                let this = self.clone();
-                spawn_local(async move {
-                    let this = this.clone();
-                    log::info!("event: accountsChanged before");
-                    this.on_accounts_changed(|addresses| {
-                        log::info!("event: accountsChanged");
+    /// request the accounts from the wallet and start following its events. while a request is
+    /// in progress further calls wait for its result rather than prompting the user again and
+    /// following the events twice
+    async fn request_connection(&self) -> Result<Vec<H160>, EthereumError> {
+        let pending = self.connecting.borrow().flow.clone();
+        if let Some(flow) = pending {
+            log::info!("connection already in progress, joining it");
+            return flow.await;
+        }
+
+        let this = self.clone();
+        let flow = async move {
+            let result = this.request_connection_once().await;
+            // cleared by the flow itself, so it's gone once done whichever caller drove it
+            this.connecting.borrow_mut().flow = None;
+            result
+        }
+        .boxed_local()
+        .shared();
+        self.connecting.borrow_mut().flow = Some(flow.clone());
+        flow.await
+    }
+
+    async fn request_connection_once(&self) -> Result<Vec<H160>, EthereumError> {
+        let addresses = self.request("eth_requestAccounts", vec![]).await?;
+        let addresses: Vec<H160> = web3::helpers::decode(addresses)?;
+        log::info!("request_accounts() {:?}", addresses);
+        self.adopt_connection(addresses.clone()).await;
+        if *self.persist.borrow() {
+            storage::set(storage::CONNECTED_KEY, "1");
+        }
+        Ok(addresses)
+    }
+
+    /// reconnect without prompting if the last session connected with persistence on (see
+    /// `set_persist_connection`) and the wallet still authorizes this site. returns whether it
+    /// reconnected
+    pub async fn restore_connection(&self) -> Result<bool, EthereumError> {
+        if self.is_read_only() || storage::get(storage::CONNECTED_KEY).is_none() {
+            return Ok(false);
+        }
+        // unlike `eth_requestAccounts` this never prompts, unauthorized sites get no accounts
+        let accounts = self.request("eth_accounts", vec![]).await?;
+        let accounts: Vec<H160> = web3::helpers::decode(accounts)?;
+        if accounts.is_empty() {
+            storage::remove(storage::CONNECTED_KEY);
+            return Ok(false);
+        }
+        log::info!("restored connection {:?}", accounts);
+        self.adopt_connection(accounts).await;
+        Ok(true)
+    }
+
+    /// remember a successful `connect()` in localStorage so `restore_connection` can reconnect
+    /// after a page reload. off by default
+    pub fn set_persist_connection(&self, persist: bool) {
+        *self.persist.borrow_mut() = persist;
+        if !persist {
+            storage::remove(storage::CONNECTED_KEY);
+        }
+    }
+
+    /// take `addresses` as the connected accounts and start following the wallet's events
+    async fn adopt_connection(&self, addresses: Vec<H160>) {
+        self.connected.set(true);
+        self.set_accounts(addresses.clone());
+        {
+            let mut idle = self.idle.borrow_mut();
+            idle.armed = true;
+            idle.touch();
+        }
+
+        self.set_chain_id(self.current_chain_id().await.ok().map(U256::from));
+
+        // a reconnect replaces the subscriptions rather than adding a second set
+        self.unsubscribe();
+        {
+            let this = self.clone();
+            self.spawn_subscription(async move {
+                let this = this.clone();
+                this.on_chain_changed(|chain_id| {
+                    // usually a decimal string, though some wallets pass the hex one through
+                    log::info!("event: chainChanged {:?}", chain_id);
+                    let chain_id = match chain::parse_chain_id(&chain_id) {
+                        Some(chain_id) => chain_id,
+                        None => {
+                            log::warn!(
+                                "ignoring chainChanged with an invalid chain id {}",
+                                chain_id
+                            );
+                            return;
+                        }
+                    };
+                    let handle = this.clone();
+                    this.debounced("chainChanged", move || handle.set_chain_id(Some(chain_id)));
+                })
+                .await;
+            });
+        }
+
+        {
+            let this = self.clone();
+            self.spawn_subscription(async move {
+                let this = this.clone();
+                log::info!("event: accountsChanged before");
+                this.on_accounts_changed(|addresses| {
+                    log::info!("event: accountsChanged");
+                    let handle = this.clone();
+                    this.debounced("accountsChanged", move || {
                         if addresses.is_empty() {
-                            this.connected.set(false);
+                            handle.connected.set(false);
                         }
-                        this.accounts.set(Some(addresses));
-                    })
-                    .await;
-                });
-            }
+                        handle.set_accounts(addresses);
+                    });
+                })
+                .await;
+            });
+        }
 
-            {
-                let this = self.clone();
-                spawn_local(async move {
-                    this.on_connect(|connect| {
-                        log::info!("event: connect: {:?}", connect);
-                        this.connected.set(true);
-                    })
-                    .await;
-                });
-            }
+        {
+            let this = self.clone();
+            self.spawn_subscription(async move {
+                this.on_connect(|connect| {
+                    log::info!("event: connect: {:?}", connect);
+                    this.connected.set(true);
+                })
+                .await;
+            });
+        }
 
-            {
-                let this = self.clone();
-                spawn_local(async move {
-                    this.on_disconnect(|chain_id| {
-                        log::info!("event: disconnect: {}", chain_id);
-                        this.connected.set(false);
-                    })
-                    .await;
-                });
-            }
+        {
+            let this = self.clone();
+            self.spawn_subscription(async move {
+                this.on_disconnect(|chain_id| {
+                    log::info!("event: disconnect: {}", chain_id);
+                    this.connected.set(false);
+                })
+                .await;
+            });
+        }
+    }
+
+    /// run a wallet event loop until `unsubscribe`
+    fn spawn_subscription<F>(&self, subscription: F)
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        let (subscription, abort) = abortable(subscription);
+        self.subscriptions.borrow_mut().push(Subscription(abort));
+        spawn_local(async move {
+            let _ = subscription.await;
+        });
+    }
+
+    /// stop the wallet event loops started by `connect()`
+    fn unsubscribe(&self) {
+        self.subscriptions.borrow_mut().clear();
+    }
+
+    /// open the wallet's account picker even while connected, by re-requesting the
+    /// `eth_accounts` permission, and adopt the accounts the user selects
+    pub async fn switch_accounts(&self) -> Result<Vec<H160>, EthereumError> {
+        self.request(
+            "wallet_requestPermissions",
+            vec![json!({ "eth_accounts": {} })],
+        )
+        .await?;
+
+        let accounts = self.request("eth_accounts", vec![]).await?;
+        let accounts: Vec<H160> = web3::helpers::decode(accounts)?;
+        self.connected.set(!accounts.is_empty());
+        self.set_accounts(accounts.clone());
+        Ok(accounts)
+    }
+
+    fn set_accounts(&self, accounts: Vec<H160>) {
+        self.js_listeners.borrow().accounts_changed(&accounts);
+        // the wallet may hand back fewer accounts than before
+        if *self.selected_account >= accounts.len() {
+            self.selected_account.set(0);
+        }
+        self.accounts.set(Some(accounts));
+    }
+
+    fn set_chain_id(&self, chain_id: Option<U256>) {
+        self.js_listeners.borrow().chain_changed(chain_id);
+        self.chain_id.set(chain_id);
+    }
+
+    /// run `apply` once no other `event` has arrived for the debounce window, so a burst of
+    /// wallet events causes a single state update
+    fn debounced<F>(&self, event: &'static str, apply: F)
+    where
+        F: FnOnce() + 'static,
+    {
+        let (generation, window_ms) = {
+            let mut debounce = self.debounce.borrow_mut();
+            (debounce.bump(event), debounce.window_ms)
         };
-        Ok(())
+        if window_ms == 0 {
+            apply();
+            return;
+        }
+        let debounce = self.debounce.clone();
+        spawn_local(async move {
+            sleep(Duration::from_millis(window_ms as u64)).await;
+            if debounce.borrow().is_latest(event, generation) {
+                apply();
+            }
+        });
+    }
+
+    /// coalesce `chainChanged` and `accountsChanged` events arriving within `ms` milliseconds
+    /// of each other, applying only the last. 0 applies every event as it arrives
+    pub fn set_event_debounce(&self, ms: u32) {
+        self.debounce.borrow_mut().window_ms = ms;
+    }
+
+    /// call a javascript function with the array of `0x`-prefixed addresses whenever the
+    /// connected accounts change, so code outside of yew can follow the connection. replaces any
+    /// previously set function and is released when the providing component unmounts
+    pub fn set_js_on_accounts_changed(&self, callback: js_sys::Function) {
+        self.js_listeners.borrow_mut().accounts_changed = Some(callback);
+    }
+
+    /// call a javascript function with the `0x`-prefixed hex chain id whenever the chain changes
+    pub fn set_js_on_chain_changed(&self, callback: js_sys::Function) {
+        self.js_listeners.borrow_mut().chain_changed = Some(callback);
+    }
+
+    /// stop calling the functions set with `set_js_on_*`
+    pub fn clear_js_listeners(&self) {
+        *self.js_listeners.borrow_mut() = JsListeners::default();
+    }
+
+    /// run `middleware` on every request before it is sent, see `RequestMiddleware`. lives as
+    /// long as the providing component
+    pub fn add_request_middleware<F>(&self, middleware: F)
+    where
+        F: Fn(&str, &[serde_json::Value]) -> Option<serde_json::Value> + 'static,
+    {
+        let middleware: RequestMiddleware = Rc::new(middleware);
+        self.middleware.borrow_mut().requests.push(middleware);
+    }
+
+    /// run `middleware` on the outcome of every request sent, eg. for logging or metrics
+    pub fn add_response_middleware<F>(&self, middleware: F)
+    where
+        F: Fn(&str, &Result<serde_json::Value, EthereumError>) + 'static,
+    {
+        let middleware: ResponseMiddleware = Rc::new(middleware);
+        self.middleware.borrow_mut().responses.push(middleware);
+    }
+
+    /// remove all middleware added with `add_*_middleware`
+    pub fn clear_middleware(&self) {
+        let mut middleware = self.middleware.borrow_mut();
+        middleware.requests.clear();
+        middleware.responses.clear();
     }
 
+    /// forget the connection locally and stop following the wallet's events, see
+    /// `revoke_permissions` to also disconnect in the wallet
     pub fn disconnect(&self) {
         log::info!("disconnect()");
+        self.unsubscribe();
+        storage::remove(storage::CONNECTED_KEY);
+        self.idle.borrow_mut().armed = false;
         self.connected.set(false);
     }
 
+    /// `wallet_revokePermissions`: give up `permissions` (eg. `"eth_accounts"`) in the wallet
+    /// itself, so the next `connect()` prompts the user again, and clear the connection state.
+    ///
+    /// `disconnect()` only forgets the connection locally while the wallet keeps the site
+    /// authorized. wallets without the method get that local disconnect only
+    pub async fn revoke_permissions(&self, permissions: Vec<String>) -> Result<(), EthereumError> {
+        let permissions: serde_json::Map<String, serde_json::Value> = permissions
+            .into_iter()
+            .map(|permission| (permission, json!({})))
+            .collect();
+        match self
+            .request("wallet_revokePermissions", vec![json!(permissions)])
+            .await
+        {
+            Ok(_) => (),
+            Err(EthereumError::Unsupported(_)) => {
+                log::warn!("wallet_revokePermissions unsupported, disconnecting locally only")
+            }
+            Err(err) => return Err(err),
+        }
+        self.disconnect();
+        self.set_accounts(Vec::new());
+        Ok(())
+    }
+
+    /// disconnect automatically once no request has been made for `ms` milliseconds, firing the
+    /// callback given to `set_on_idle`. `None` turns the policy off
+    pub fn set_idle_timeout(&self, ms: Option<u32>) {
+        let generation = {
+            let mut idle = self.idle.borrow_mut();
+            idle.timeout_ms = ms;
+            idle.generation = idle.generation.wrapping_add(1);
+            idle.generation
+        };
+        if ms.is_some() {
+            let this = self.clone();
+            spawn_local(async move { this.watch_idle(generation).await });
+        }
+    }
+
+    /// callback fired when the idle timeout disconnects the session
+    pub fn set_on_idle(&self, callback: Option<Callback<()>>) {
+        self.idle.borrow_mut().on_idle = callback;
+    }
+
+    async fn watch_idle(&self, generation: u32) {
+        loop {
+            let remaining = match self.idle.borrow().remaining(generation) {
+                Some(remaining) => remaining,
+                None => return,
+            };
+            if remaining > 0.0 {
+                sleep(Duration::from_millis(remaining.ceil() as u64)).await;
+                continue;
+            }
+
+            let on_idle = {
+                let mut idle = self.idle.borrow_mut();
+                idle.touch();
+                if !idle.armed {
+                    continue;
+                }
+                idle.on_idle.clone()
+            };
+            log::info!("idle timeout reached");
+            self.disconnect();
+            if let Some(on_idle) = on_idle {
+                on_idle.emit(());
+            }
+        }
+    }
+
+    /// whether an account is connected, ie. the user authorized this site in their wallet
     pub fn connected(&self) -> bool {
         *self.connected
     }
 
+    /// where the connection stands, in one value for components to match on
+    pub fn status(&self) -> ConnectionStatus {
+        if *self.connected && self.address().is_some() {
+            ConnectionStatus::Connected
+        } else if *self.connect_pending {
+            ConnectionStatus::Connecting
+        } else if self.last_error.is_some() {
+            ConnectionStatus::Errored
+        } else {
+            ConnectionStatus::Disconnected
+        }
+    }
+
+    /// EIP-1193 `isConnected()`: whether the provider can reach its node and serve requests.
+    ///
+    /// this is independent of `connected()`. a wallet can have authorized accounts while its
+    /// node is down, and be able to serve requests before any account is connected. providers
+    /// without `isConnected` are assumed to be connected, as is the endpoint in read-only mode
+    pub fn provider_is_connected(&self) -> bool {
+        let provider = match &self.provider {
+            Some(provider) => provider,
+            None => return self.rpc.is_some(),
+        };
+        let provider: &JsValue = provider.as_ref();
+        let is_connected = Reflect::get(provider, &JsValue::from("isConnected"))
+            .ok()
+            .and_then(|is_connected| is_connected.dyn_into::<js_sys::Function>().ok());
+        match is_connected {
+            Some(is_connected) => is_connected
+                .call0(provider)
+                .map(|connected| connected.as_bool().unwrap_or(false))
+                .unwrap_or_else(|err| {
+                    log::error!("isConnected() threw: {}", js_error_to_string(&err));
+                    false
+                }),
+            None => true,
+        }
+    }
+
+    /// the injected wallet as it announces itself through EIP-6963, or as identified by its
+    /// flags when it doesn't. `None` in read-only mode. see
+    /// `use_wallet_info` to follow it from a component
+    pub fn wallet_info(&self) -> Option<WalletInfo> {
+        self.provider
+            .as_ref()
+            .map(|provider| WalletInfo::detect(provider.as_ref()))
+    }
+
+    /// the selected account, the first one the wallet lists unless `select_account` picked
+    /// another
     pub fn address(&self) -> Option<&H160> {
-        self.accounts.as_ref().and_then(|a| a.first())
+        self.accounts
+            .as_ref()
+            .and_then(|a| a.get(*self.selected_account).or_else(|| a.first()))
+    }
+
+    /// every account the wallet shares with the site, the one it considers active first
+    pub fn accounts(&self) -> Option<&Vec<H160>> {
+        self.accounts.as_ref()
+    }
+
+    /// make the account at `index` of `accounts()` the one `address()` returns and requests
+    /// are made from. out of range indices are ignored
+    pub fn select_account(&self, index: usize) {
+        let len = self.accounts.as_ref().map_or(0, Vec::len);
+        if index < len {
+            self.selected_account.set(index);
+        } else {
+            log::warn!("account index {} out of range, {} accounts", index, len);
+        }
+    }
+
+    /// index in `accounts()` of the selected account
+    pub fn selected_account(&self) -> usize {
+        *self.selected_account
     }
 
     /// returns the chain_id as a decimal. returns None on invalid chain values
@@ -119,24 +625,1015 @@ impl UseEthereumHandle {
             .map(|chain_id| format!("0x{:X}", chain_id))
     }
 
-    pub fn display_short_address(&self) -> String {
+    /// native currency of the current chain, looked up in the chains registry
+    pub fn native_currency(&self) -> Option<BaseCurrency> {
+        self.chain_id()
+            .and_then(chain::from_chain_id)
+            .map(|chain| chain.native_currency)
+    }
+
+    /// balance of `address` in the smallest unit of the chain's native currency (eg. wei)
+    pub async fn get_balance(&self, address: H160) -> Result<U256, EthereumError> {
+        let balance = self
+            .request("eth_getBalance", vec![json!(address), json!("latest")])
+            .await?;
+        Ok(web3::helpers::decode(balance)?)
+    }
+
+    /// balances of `addresses`, in their order, read with a single multicall. chains without
+    /// Multicall3 are queried with a batch of `eth_getBalance` instead
+    pub async fn get_balances(&self, addresses: &[H160]) -> Result<Vec<U256>, EthereumError> {
+        if addresses.is_empty() {
+            return Ok(Vec::new());
+        }
+        let calls: Vec<Call3> = addresses
+            .iter()
+            .map(multicall::get_eth_balance_call)
+            .collect();
+        let results = match self.multicall(&calls).await {
+            Ok(results) => results,
+            Err(err) => {
+                log::warn!(
+                    "multicall of balances failed, falling back to eth_getBalance: {}",
+                    err
+                );
+                let requests = addresses
+                    .iter()
+                    .map(|address| ("eth_getBalance", vec![json!(address), json!("latest")]))
+                    .collect();
+                return self
+                    .batch(requests)
+                    .await
+                    .into_iter()
+                    .map(|balance| -> Result<U256, EthereumError> {
+                        Ok(web3::helpers::decode(balance?)?)
+                    })
+                    .collect();
+            }
+        };
+        results
+            .into_iter()
+            .map(|data| -> Result<U256, EthereumError> {
+                let data = data.ok_or_else(|| {
+                    DecodeError::InvalidValue(String::from("getEthBalance failed"))
+                })?;
+                Ok(abi::decode_uint(&data)?)
+            })
+            .collect()
+    }
+
+    /// balance of `address` at a past `block`. providers without archival state fail with
+    /// `EthereumError::ArchivalUnsupported`
+    pub async fn get_balance_at(
+        &self,
+        address: H160,
+        block: BlockTag,
+    ) -> Result<U256, EthereumError> {
+        let balance = self
+            .request("eth_getBalance", vec![json!(address), json!(block)])
+            .await
+            .map_err(|err| match err {
+                EthereumError::Rpc { message, .. }
+                    if block != BlockTag::Latest && is_missing_state(&message) =>
+                {
+                    EthereumError::ArchivalUnsupported(block)
+                }
+                err => err,
+            })?;
+        Ok(web3::helpers::decode(balance)?)
+    }
+
+    /// `eth_call` against the latest block, returning the raw return data
+    pub async fn call(&self, to: H160, data: Bytes) -> Result<Bytes, EthereumError> {
+        let request = CallRequest {
+            to: Some(to),
+            data: Some(data),
+            ..Default::default()
+        };
+        let result = self
+            .request("eth_call", vec![json!(request), json!("latest")])
+            .await?;
+        Ok(web3::helpers::decode(result)?)
+    }
+
+    /// `eth_call` whose return data is decoded by the caller-supplied `decode`, eg.
+    /// `handle.call_decoded(token, data, abi::decode_uint)`
+    pub async fn call_decoded<T>(
+        &self,
+        to: H160,
+        data: Bytes,
+        decode: impl Fn(&[u8]) -> Result<T, DecodeError>,
+    ) -> Result<T, EthereumError> {
+        let bytes = self.call(to, data).await?;
+        Ok(decode(&bytes.0)?)
+    }
+
+    /// several reads in a single `eth_call` through Multicall3, in the order of `calls`. calls
+    /// that failed are `None`
+    pub async fn multicall(&self, calls: &[Call3]) -> Result<Vec<Option<Vec<u8>>>, EthereumError> {
+        let data = multicall::encode_aggregate3(calls);
+        self.call_decoded(
+            multicall::address(),
+            data.into(),
+            multicall::decode_aggregate3,
+        )
+        .await
+    }
+
+    /// `decimals()` of an ERC20 token. prefer `Erc20DecimalsCache::decimals` in components so the
+    /// value is only read once
+    pub async fn erc20_decimals(&self, token: H160) -> Result<u8, EthereumError> {
+        let data = abi::encode_call(abi::selector("decimals()"), &[]);
+        self.call_decoded(token, data.into(), abi::decode_u8).await
+    }
+
+    /// `balanceOf(account)` of an ERC20 token, in its smallest unit
+    pub async fn erc20_balance_of(
+        &self,
+        token: H160,
+        account: H160,
+    ) -> Result<U256, EthereumError> {
+        let data = abi::encode_call(
+            abi::selector("balanceOf(address)"),
+            &[abi::encode_address(&account)],
+        );
+        self.call_decoded(token, data.into(), abi::decode_uint)
+            .await
+    }
+
+    /// `erc20_balance_of` of `asset` for `owner`, as a decimal string using `asset.decimals`,
+    /// eg. `"1.5"`
+    pub async fn erc20_balance_display(
+        &self,
+        asset: &ERC20Asset,
+        owner: H160,
+    ) -> Result<String, EthereumError> {
+        let token = asset.address.trim().parse::<H160>().map_err(|_| {
+            EthereumError::InvalidRequest(format!("invalid token address {}", asset.address))
+        })?;
+        let balance = self.erc20_balance_of(token, owner).await?;
+        Ok(units::format_units(balance, asset.decimals))
+    }
+
+    /// ERC20 `transfer(to, amount)` from the connected account, `amount` in the token's smallest
+    /// unit. returns the transaction hash once the wallet has broadcast it
+    pub async fn erc20_transfer(
+        &self,
+        token: H160,
+        to: H160,
+        amount: U256,
+    ) -> Result<H256, EthereumError> {
+        let from = *self.address().ok_or(EthereumError::NotConnected)?;
+        let data = abi::encode_call(
+            abi::selector("transfer(address,uint256)"),
+            &[abi::encode_address(&to), abi::encode_uint(amount)],
+        );
+        self.send_transaction(&TransactionRequest {
+            from: Some(from),
+            to: Some(token),
+            value: Some(U256::zero()),
+            data: Some(data.into()),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// ERC-165 `supportsInterface(interface_id)` of `contract`, with ids such as
+    /// `erc165::ERC721`. contracts without ERC-165, whose call reverts or returns nothing, don't
+    /// support any interface
+    pub async fn supports_interface(
+        &self,
+        contract: H160,
+        interface_id: [u8; 4],
+    ) -> Result<bool, EthereumError> {
+        let data = erc165::supports_interface_call(interface_id);
+        match self
+            .call_decoded(contract, data.into(), abi::decode_bool)
+            .await
+        {
+            Err(EthereumError::Revert { .. }) => Ok(false),
+            Err(EthereumError::Rpc { message, .. }) if message.contains("revert") => Ok(false),
+            Err(EthereumError::Decode(_)) => Ok(false),
+            result => result,
+        }
+    }
+
+    /// `eth_sendTransaction`, returning the transaction hash once the wallet has broadcast it.
+    /// `from` defaults to the connected account
+    pub async fn send_transaction(&self, tx: &TransactionRequest) -> Result<H256, EthereumError> {
+        tx.validate()?;
+        let mut tx = tx.clone();
+        if tx.from.is_none() {
+            tx.from = Some(*self.address().ok_or(EthereumError::NotConnected)?);
+        }
+
+        let hash = self.request("eth_sendTransaction", vec![json!(tx)]).await?;
+        Ok(web3::helpers::decode(hash)?)
+    }
+
+    /// `send_transaction` of `value` wei, and optionally calldata, from the connected account to
+    /// `to`. quantities are sent as minimal hex, so a zero value is `"0x0"`
+    pub async fn send_value(
+        &self,
+        to: H160,
+        value: U256,
+        data: Option<Vec<u8>>,
+    ) -> Result<H256, EthereumError> {
+        self.send_transaction(&TransactionRequest {
+            to: Some(to),
+            value: Some(value),
+            data: data.map(Bytes::from),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// `eth_estimateGas` of sending `value` wei and optionally calldata to `to`, from the
+    /// connected account if there is one
+    pub async fn estimate_gas(
+        &self,
+        to: H160,
+        value: U256,
+        data: Option<Vec<u8>>,
+    ) -> Result<U256, EthereumError> {
+        self.estimate_transaction_gas(&TransactionRequest {
+            from: self.address().copied(),
+            to: Some(to),
+            value: Some(value),
+            data: data.map(Bytes::from),
+            ..Default::default()
+        })
+        .await
+    }
+
+    async fn estimate_transaction_gas(
+        &self,
+        tx: &TransactionRequest,
+    ) -> Result<U256, EthereumError> {
+        let gas = self.request("eth_estimateGas", vec![json!(tx)]).await?;
+        Ok(web3::helpers::decode(gas)?)
+    }
+
+    /// `eth_gasPrice`, the legacy gas price in wei the node suggests
+    pub async fn gas_price(&self) -> Result<U256, EthereumError> {
+        let gas_price = self.request("eth_gasPrice", vec![]).await?;
+        Ok(web3::helpers::decode(gas_price)?)
+    }
+
+    /// `eth_maxPriorityFeePerGas`, the EIP-1559 tip in wei the node suggests. providers without
+    /// the method fail with `Unsupported`, see `suggest_fees` for an estimate from fee history
+    pub async fn max_priority_fee_per_gas(&self) -> Result<U256, EthereumError> {
+        let tip = self.request("eth_maxPriorityFeePerGas", vec![]).await?;
+        Ok(web3::helpers::decode(tip)?)
+    }
+
+    /// fill in everything `tx` leaves unset so it can be signed offline: the chain id, the
+    /// pending nonce of the sender, a gas estimate and fees. EIP-1559 fees from `suggest_fees`
+    /// are used unless `tx` sets a `gas_price` or the chain has no base fee
+    pub async fn build_unsigned_tx(
+        &self,
+        tx: &TransactionRequest,
+    ) -> Result<UnsignedTransaction, EthereumError> {
+        tx.validate()?;
+        let mut tx = tx.clone();
+        let from = match tx.from {
+            Some(from) => from,
+            None => *self.address().ok_or(EthereumError::NotConnected)?,
+        };
+        tx.from = Some(from);
+
+        let chain_id = self.current_chain_id().await?;
+        let nonce = match tx.nonce {
+            Some(nonce) => nonce,
+            None => self.transaction_count(from, BlockTag::Pending).await?,
+        };
+        let gas = match tx.gas {
+            Some(gas) => gas,
+            None => self.estimate_transaction_gas(&tx).await?,
+        };
+        let fees = match (
+            tx.gas_price,
+            tx.max_fee_per_gas,
+            tx.max_priority_fee_per_gas,
+        ) {
+            (Some(gas_price), _, _) => TransactionFees::Legacy { gas_price },
+            (None, Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) => {
+                TransactionFees::Eip1559 {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                }
+            }
+            (None, max_fee, priority_fee) => match self.suggest_fees(FeeLevel::Standard).await {
+                Ok(suggested) if !suggested.base_fee_per_gas.is_zero() => {
+                    let max_priority_fee_per_gas =
+                        priority_fee.unwrap_or(suggested.max_priority_fee_per_gas);
+                    TransactionFees::Eip1559 {
+                        max_fee_per_gas: max_fee
+                            .unwrap_or(suggested.max_fee_per_gas.max(max_priority_fee_per_gas)),
+                        max_priority_fee_per_gas,
+                    }
+                }
+                // chains without EIP-1559 fail `eth_feeHistory` or report no base fee
+                _ => TransactionFees::Legacy {
+                    gas_price: self.gas_price().await?,
+                },
+            },
+        };
+
+        Ok(UnsignedTransaction {
+            chain_id,
+            nonce,
+            gas,
+            fees,
+            to: tx.to,
+            value: tx.value.unwrap_or_default(),
+            data: tx.data.unwrap_or_default(),
+        })
+    }
+
+    /// `personal_sign` of `message` by the connected account, as a `0x`-prefixed 65 byte
+    /// signature. the message is sent as the hex of its utf-8 bytes, which wallets show decoded
+    pub async fn personal_sign(&self, message: &str) -> Result<String, EthereumError> {
+        let address = self.address().ok_or(EthereumError::NotConnected)?;
+        let signature = self
+            .request(
+                "personal_sign",
+                vec![json!(Bytes::from(message.as_bytes())), json!(address)],
+            )
+            .await?;
+        Ok(web3::helpers::decode(signature)?)
+    }
+
+    /// sign `siwe` with `personal_sign` as the connected account on the current chain. fails
+    /// with `InvalidRequest` if `siwe` doesn't pass `Siwe::validate`
+    pub async fn sign_in(&self, siwe: &Siwe) -> Result<SiweSignature, EthereumError> {
+        siwe.validate()?;
+        let address = *self.address().ok_or(EthereumError::NotConnected)?;
+        let chain_id = self.chain_id().ok_or(EthereumError::NotConnected)?;
+        let mut siwe = siwe.clone().address(address).chain_id(chain_id);
+        if !siwe.has_issued_at() {
+            let now = js_sys::Date::new_0().to_iso_string();
+            siwe = siwe.issued_at(String::from(now));
+        }
+        let message = siwe
+            .message()
+            .expect("address, chain id and issued at are all set");
+        let signature = self.personal_sign(&message).await?;
+        Ok(SiweSignature { message, signature })
+    }
+
+    /// the account that signed `message` with `personal_sign`, recovered by the wallet through
+    /// `personal_ecRecover` so no secp256k1 code has to run in wasm. with the `signing` feature,
+    /// wallets lacking the method fall back to `recover_signer`, which is preferable anyway when
+    /// available as it saves the rpc round-trip
+    pub async fn personal_ec_recover(
+        &self,
+        message: &str,
+        signature: &str,
+    ) -> Result<H160, EthereumError> {
+        let result = self
+            .request(
+                "personal_ecRecover",
+                vec![json!(Bytes::from(message.as_bytes())), json!(signature)],
+            )
+            .await;
+        match result {
+            Ok(signer) => Ok(web3::helpers::decode(signer)?),
+            #[cfg(feature = "signing")]
+            Err(EthereumError::Unsupported(_)) => crate::recover_signer(message, signature),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// `eth_getTransactionCount` of `address` at `block`, ie. the nonce its next transaction
+    /// after that block uses
+    pub async fn transaction_count(
+        &self,
+        address: H160,
+        block: BlockTag,
+    ) -> Result<U256, EthereumError> {
+        let count = self
+            .request(
+                "eth_getTransactionCount",
+                vec![json!(address), json!(block)],
+            )
+            .await?;
+        Ok(web3::helpers::decode(count)?)
+    }
+
+    /// the nonce for the connected account's next transaction, counting the ones still pending
+    /// so queued transactions don't collide
+    pub async fn next_nonce(&self) -> Result<U256, EthereumError> {
+        let address = *self.address().ok_or(EthereumError::NotConnected)?;
+        self.transaction_count(address, BlockTag::Pending).await
+    }
+
+    /// number of transactions of the connected account waiting in the mempool, `None` when
+    /// there are none. a lasting gap means a transaction is stuck, eg. underpriced, and the ones
+    /// after it can't be mined until it is sped up or cancelled
+    pub async fn pending_nonce_gap(&self) -> Result<Option<u32>, EthereumError> {
+        let address = *self.address().ok_or(EthereumError::NotConnected)?;
+        let latest = self.transaction_count(address, BlockTag::Latest).await?;
+        let pending = self.transaction_count(address, BlockTag::Pending).await?;
+        // some nodes momentarily report fewer pending than mined transactions
+        if pending <= latest {
+            return Ok(None);
+        }
+        Ok(Some((pending - latest).min(U256::from(u32::MAX)).as_u32()))
+    }
+
+    /// `eth_sendRawTransaction` of a transaction signed elsewhere, eg.
+    /// `UnsignedTransaction::encode_signed`, returning its hash
+    pub async fn send_raw_transaction(&self, raw: Bytes) -> Result<H256, EthereumError> {
+        let hash = self
+            .request("eth_sendRawTransaction", vec![json!(raw)])
+            .await?;
+        Ok(web3::helpers::decode(hash)?)
+    }
+
+    /// `eth_getTransactionReceipt`, `None` while the transaction is pending
+    pub async fn get_transaction_receipt(
+        &self,
+        tx_hash: H256,
+    ) -> Result<Option<TransactionReceipt>, EthereumError> {
+        let receipt = self
+            .request("eth_getTransactionReceipt", vec![json!(tx_hash)])
+            .await?;
+        let receipt: Option<web3::types::TransactionReceipt> = web3::helpers::decode(receipt)?;
+        Ok(receipt.map(TransactionReceipt::from))
+    }
+
+    /// wait until `tx_hash` is mined and buried under `confirmations` blocks (counting its own),
+    /// polling every second. a reverted transaction is an `EthereumError::TransactionReverted`
+    pub async fn wait_for_receipt(
+        &self,
+        tx_hash: H256,
+        confirmations: u64,
+    ) -> Result<TransactionReceipt, EthereumError> {
+        self.wait_for_receipt_with(tx_hash, confirmations, &PollConfig::default(), None)
+            .await
+    }
+
+    /// `wait_for_receipt` polling as configured by `poll`. `on_progress` is called with the
+    /// number of each poll, eg. to show "checking… (attempt 5)"
+    pub async fn wait_for_receipt_with(
+        &self,
+        tx_hash: H256,
+        confirmations: u64,
+        poll: &PollConfig,
+        on_progress: Option<Callback<u32>>,
+    ) -> Result<TransactionReceipt, EthereumError> {
+        let mut attempt = 0;
+        let mut next_attempt = || {
+            attempt += 1;
+            if let Some(on_progress) = &on_progress {
+                on_progress.emit(attempt);
+            }
+            attempt
+        };
+
+        let (receipt, mined) = loop {
+            let attempt = next_attempt();
+            if let Some(receipt) = self.get_transaction_receipt(tx_hash).await? {
+                if let Some(mined) = receipt.block_number() {
+                    break (receipt, mined);
+                }
+            }
+            sleep(Duration::from_millis(poll.delay_ms(attempt) as u64)).await;
+        };
+
+        if receipt.outcome == TxOutcome::Reverted {
+            return Err(EthereumError::TransactionReverted(tx_hash));
+        }
+
+        loop {
+            let latest = self.block_number().await?;
+            if latest.saturating_sub(mined) + 1 >= confirmations {
+                return Ok(receipt);
+            }
+            let attempt = next_attempt();
+            sleep(Duration::from_millis(poll.delay_ms(attempt) as u64)).await;
+        }
+    }
+
+    /// number of the most recent block
+    pub async fn block_number(&self) -> Result<u64, EthereumError> {
+        let number = self.request("eth_blockNumber", vec![]).await?;
+        Ok(web3::helpers::decode::<U64>(number)?.as_u64())
+    }
+
+    /// unix timestamp of a block, `None` if the block is unknown to the provider
+    pub async fn block_timestamp(&self, number: u64) -> Result<Option<u64>, EthereumError> {
+        let block = self
+            .request(
+                "eth_getBlockByNumber",
+                vec![json!(U64::from(number)), json!(false)],
+            )
+            .await?;
+        let block: Option<Block<H256>> = web3::helpers::decode(block)?;
+        Ok(block.map(|block| block.timestamp.as_u64()))
+    }
+
+    /// EIP-1559 fees for `level`, based on the tips paid in the last `FEE_HISTORY_BLOCKS`
+    /// blocks
+    pub async fn suggest_fees(&self, level: FeeLevel) -> Result<FeeSuggestion, EthereumError> {
+        Ok(self.raw_fee_history().await?.suggest(level))
+    }
+
+    /// `eth_feeHistory` of the `block_count` blocks up to `newest_block`, with the priority fees
+    /// paid at each of `reward_percentiles` (ascending, 0 to 100)
+    pub async fn fee_history(
+        &self,
+        block_count: u64,
+        newest_block: BlockTag,
+        reward_percentiles: &[f64],
+    ) -> Result<FeeHistory, EthereumError> {
+        let history = self
+            .request(
+                "eth_feeHistory",
+                vec![
+                    json!(U64::from(block_count)),
+                    json!(newest_block),
+                    json!(reward_percentiles),
+                ],
+            )
+            .await?;
+        Ok(web3::helpers::decode(history)?)
+    }
+
+    async fn raw_fee_history(&self) -> Result<RawFeeHistory, EthereumError> {
+        let history = self
+            .request(
+                "eth_feeHistory",
+                vec![
+                    json!(U64::from(FEE_HISTORY_BLOCKS)),
+                    json!("latest"),
+                    json!(RawFeeHistory::percentiles()),
+                ],
+            )
+            .await?;
+        Ok(web3::helpers::decode(history)?)
+    }
+
+    /// mean seconds between the last `FEE_HISTORY_BLOCKS` blocks, `DEFAULT_BLOCK_TIME_SECS` if
+    /// their timestamps can't be read
+    pub async fn average_block_time(&self) -> Result<f64, EthereumError> {
+        let latest = self.block_number().await?;
+        let first = latest.saturating_sub(FEE_HISTORY_BLOCKS);
+        if first == latest {
+            return Ok(DEFAULT_BLOCK_TIME_SECS);
+        }
+        match (
+            self.block_timestamp(first).await?,
+            self.block_timestamp(latest).await?,
+        ) {
+            (Some(start), Some(end)) if end > start => {
+                Ok((end - start) as f64 / (latest - first) as f64)
+            }
+            _ => Ok(DEFAULT_BLOCK_TIME_SECS),
+        }
+    }
+
+    /// best-effort estimate of the seconds until a transaction paying the `suggest_fees` of
+    /// `level` is mined, for hints like "~30s to confirm".
+    ///
+    /// while recent blocks were at most half full every level is expected in the next block,
+    /// otherwise a level waits `FeeLevel::expected_blocks`. actual times vary with the mempool
+    /// and can be far longer when fees spike
+    pub async fn estimate_confirmation_time(&self, level: FeeLevel) -> Result<u32, EthereumError> {
+        let history = self.raw_fee_history().await?;
+        let blocks = if history.congested() {
+            level.expected_blocks()
+        } else {
+            1
+        };
+        let block_time = self.average_block_time().await?;
+        Ok((blocks as f64 * block_time).ceil() as u32)
+    }
+
+    /// `eth_getLogs`
+    pub async fn get_logs(&self, filter: &LogFilter) -> Result<Vec<Log>, EthereumError> {
+        let logs = self.request("eth_getLogs", vec![json!(filter)]).await?;
+        Ok(web3::helpers::decode(logs)?)
+    }
+
+    /// `eth_getLogs` over the inclusive block range `from..=to`, split into queries of at most
+    /// `chunk_size` blocks since most providers cap the range of a single query. the
+    /// `from_block`/`to_block` of `filter` are ignored
+    pub async fn get_logs_chunked(
+        &self,
+        filter: &LogFilter,
+        from: u64,
+        to: u64,
+        chunk_size: u64,
+    ) -> Result<Vec<Log>, EthereumError> {
+        let chunk_size = chunk_size.max(1);
+        let mut logs = Vec::new();
+        let mut start = from;
+        while start <= to {
+            let end = to.min(start.saturating_add(chunk_size - 1));
+            let chunk = LogFilter {
+                from_block: Some(start.into()),
+                to_block: Some(end.into()),
+                ..filter.clone()
+            };
+            logs.extend(self.get_logs(&chunk).await?);
+            if end == u64::MAX {
+                break;
+            }
+            start = end + 1;
+        }
+        Ok(logs)
+    }
+
+    /// `net_listening`, whether the node behind the provider is listening for peers
+    pub async fn is_listening(&self) -> Result<bool, EthereumError> {
+        let listening = self.request("net_listening", vec![]).await?;
+        Ok(web3::helpers::decode(listening)?)
+    }
+
+    /// `web3_clientVersion`, eg. `Geth/v1.10.26-stable/linux-amd64/go1.19.3`. use
+    /// `ClientVersion::parse` to pick it apart
+    pub async fn client_version(&self) -> Result<String, EthereumError> {
+        let version = self.request("web3_clientVersion", vec![]).await?;
+        Ok(web3::helpers::decode(version)?)
+    }
+
+    /// EIP-712: `eth_signTypedData_v4` of a json payload holding `types`, `primaryType`, `domain`
+    /// and `message`, signed by the connected account
+    pub async fn sign_typed_data(
+        &self,
+        typed_data: serde_json::Value,
+    ) -> Result<String, EthereumError> {
+        for key in ["domain", "types", "message"] {
+            if typed_data.get(key).is_none() {
+                return Err(EthereumError::InvalidRequest(format!(
+                    "typed data is missing `{}`",
+                    key
+                )));
+            }
+        }
+        let address = self.address().ok_or(EthereumError::NotConnected)?;
+
+        // the signer comes first, some wallets silently sign garbage if the order is swapped
+        let signature = self
+            .request(
+                "eth_signTypedData_v4",
+                vec![json!(address), json!(typed_data.to_string())],
+            )
+            .await?;
+        Ok(web3::helpers::decode(signature)?)
+    }
+
+    /// EIP-712 signature of a `TypedData` struct
+    pub async fn sign_typed_data_struct<T: TypedData>(
+        &self,
+        data: &T,
+    ) -> Result<String, EthereumError> {
+        self.sign_typed_data(data.to_json()).await
+    }
+
+    /// ERC-2612: sign a gasless approval of `value` of `token` for `spender`, valid until the
+    /// unix timestamp `deadline`. the token's name, version and the owner's nonce are read from
+    /// the contract. tokens without `DOMAIN_SEPARATOR()`/`nonces(address)` are `Unsupported`
+    pub async fn sign_permit(
+        &self,
+        token: H160,
+        spender: H160,
+        value: U256,
+        deadline: U256,
+    ) -> Result<PermitSignature, EthereumError> {
+        let owner = *self.address().ok_or(EthereumError::NotConnected)?;
+        // tokens without the function revert or answer with nothing, other failures are real
+        let unsupported = |err| match err {
+            EthereumError::Revert { .. } | EthereumError::Decode(_) => {
+                EthereumError::Unsupported(String::from("ERC-2612 permit"))
+            }
+            err => err,
+        };
+
+        let nonce_call = abi::encode_call(
+            abi::selector("nonces(address)"),
+            &[abi::encode_address(&owner)],
+        );
+        let nonce = self
+            .call_decoded(token, nonce_call.into(), abi::decode_uint)
+            .await
+            .map_err(unsupported)?;
+        let separator_call = abi::encode_call(abi::selector("DOMAIN_SEPARATOR()"), &[]);
+        let separator = self
+            .call_decoded(token, separator_call.into(), |data| {
+                abi::word(data, 0).map(H256::from_slice)
+            })
+            .await
+            .map_err(unsupported)?;
+
+        let name_call = abi::encode_call(abi::selector("name()"), &[]);
+        let name = self
+            .call_decoded(token, name_call.into(), abi::decode_string)
+            .await?;
+        // version() is optional, most tokens without it use "1"
+        let version_call = abi::encode_call(abi::selector("version()"), &[]);
+        let version = self
+            .call_decoded(token, version_call.into(), abi::decode_string)
+            .await
+            .unwrap_or_else(|_| String::from("1"));
+        let chain_id = match self.chain_id() {
+            Some(chain_id) => U256::from(chain_id),
+            None => web3::helpers::decode(self.request("eth_chainId", vec![]).await?)?,
+        };
+
+        let domain = Eip712Domain {
+            name: Some(name),
+            version: Some(version),
+            chain_id: Some(chain_id),
+            verifying_contract: Some(token),
+            salt: None,
+        };
+        if domain.separator() != separator {
+            log::warn!(
+                "DOMAIN_SEPARATOR of {:?} doesn't match its name/version, the permit may be rejected",
+                token
+            );
+        }
+
+        let permit = Permit {
+            domain,
+            owner,
+            spender,
+            value,
+            nonce,
+            deadline,
+        };
+        let signature = self.sign_typed_data_struct(&permit).await?;
+        PermitSignature::from_hex(&signature).ok_or_else(|| {
+            EthereumError::Decode(DecodeError::InvalidValue(format!(
+                "{} is not a 65 byte signature",
+                signature
+            )))
+        })
+    }
+
+    /// resolve ENS names through a mainnet rpc endpoint, so resolution works while the wallet
+    /// is on another chain. `None` goes back to resolving through the wallet, which then has to
+    /// be on mainnet
+    pub fn set_ens_rpc_url(&self, url: Option<&str>) -> Result<(), EthereumError> {
+        let rpc = url.map(Http::new).transpose()?;
+        *self.ens_rpc.borrow_mut() = rpc;
+        Ok(())
+    }
+
+    /// send the reads `is_read_method` picks out to this rpc endpoint rather than the wallet,
+    /// which may throttle them or cap `eth_getLogs` ranges. transactions, signing and `wallet_*`
+    /// requests still go to the wallet. the endpoint's `eth_chainId` is asked on first use, reads
+    /// go to the wallet as well while it differs from `chain_id()`. `None` sends everything to
+    /// the wallet again
+    pub fn set_read_rpc_url(&self, url: Option<&str>) -> Result<(), EthereumError> {
+        let rpc = url.map(Http::new).transpose()?;
+        *self.read_rpc.borrow_mut() = rpc.map(ReadRpc::new);
+        Ok(())
+    }
+
+    /// the endpoint set with `set_read_rpc_url`, as long as it serves the current chain
+    async fn read_rpc(&self) -> Option<Http> {
+        let read_rpc = self.read_rpc.borrow().clone()?;
+        let chain_id = match read_rpc.chain_id.get() {
+            Some(chain_id) => chain_id,
+            None => {
+                let chain_id = read_rpc
+                    .rpc
+                    .execute("eth_chainId", vec![])
+                    .await
+                    .and_then(web3::helpers::decode::<U64>);
+                match chain_id {
+                    Ok(chain_id) => {
+                        read_rpc.chain_id.set(Some(chain_id.as_u64()));
+                        chain_id.as_u64()
+                    }
+                    Err(err) => {
+                        log::warn!("failed to read the chain id of the read rpc: {}", err);
+                        return None;
+                    }
+                }
+            }
+        };
+        if Some(chain_id) == self.chain_id() {
+            Some(read_rpc.rpc)
+        } else {
+            log::debug!(
+                "read rpc serves chain {} rather than {:?}, reading from the wallet",
+                chain_id,
+                self.chain_id()
+            );
+            None
+        }
+    }
+
+    /// `eth_call` on mainnet for ENS lookups, see the `ens` module
+    async fn ens_call(&self, to: H160, data: Vec<u8>) -> Result<Vec<u8>, EthereumError> {
+        let rpc = self.ens_rpc.borrow().clone();
+        let data = match rpc {
+            Some(rpc) => {
+                let request = CallRequest {
+                    to: Some(to),
+                    data: Some(data.into()),
+                    ..Default::default()
+                };
+                let result = rpc
+                    .execute("eth_call", vec![json!(request), json!("latest")])
+                    .await?;
+                web3::helpers::decode::<Bytes>(result)?
+            }
+            None if self.chain_id() == Some(ens::ENS_CHAIN_ID) => {
+                self.call(to, data.into()).await?
+            }
+            None => {
+                return Err(EthereumError::WrongNetwork {
+                    expected: ens::ENS_CHAIN_ID,
+                    actual: self.chain_id(),
+                })
+            }
+        };
+        Ok(data.0)
+    }
+
+    async fn ens_resolver(&self, node: [u8; 32]) -> Result<Option<H160>, EthereumError> {
+        let data = self
+            .ens_call(ens::registry(), ens::resolver_call(node))
+            .await?;
+        Ok(ens::decode_optional_address(&data)?)
+    }
+
+    /// address an ENS name such as `vitalik.eth` points to, `None` if it isn't set
+    pub async fn resolve_ens(&self, name: &str) -> Result<Option<H160>, EthereumError> {
+        let name = name.to_lowercase();
+        if let Some(address) = self.ens_cache.borrow().get(&name) {
+            return Ok(*address);
+        }
+
+        let node = ens::node(&name);
+        let address = match self.ens_resolver(node).await? {
+            Some(resolver) => {
+                let data = self.ens_call(resolver, ens::addr_call(node)).await?;
+                ens::decode_optional_address(&data)?
+            }
+            None => None,
+        };
+        self.ens_cache.borrow_mut().insert(name, address);
+        Ok(address)
+    }
+
+    /// `multicall` on mainnet for ENS lookups
+    async fn ens_multicall(&self, calls: &[Call3]) -> Result<Vec<Option<Vec<u8>>>, EthereumError> {
+        let data = self
+            .ens_call(multicall::address(), multicall::encode_aggregate3(calls))
+            .await?;
+        Ok(multicall::decode_aggregate3(&data)?)
+    }
+
+    /// resolve several ENS names, in the order of `names`, batching the registry and resolver
+    /// reads into two multicalls. falls back to resolving one by one if the multicall fails
+    pub async fn resolve_ens_batch(
+        &self,
+        names: &[&str],
+    ) -> Result<Vec<Option<H160>>, EthereumError> {
+        let names: Vec<String> = names.iter().map(|name| name.to_lowercase()).collect();
+        let mut missing: Vec<&String> = names
+            .iter()
+            .filter(|name| !self.ens_cache.borrow().contains_key(*name))
+            .collect();
+        missing.sort();
+        missing.dedup();
+
+        if !missing.is_empty() {
+            match self.resolve_ens_uncached(&missing).await {
+                Ok(addresses) => {
+                    let mut cache = self.ens_cache.borrow_mut();
+                    for (name, address) in missing.into_iter().zip(addresses) {
+                        cache.insert(name.clone(), address);
+                    }
+                }
+                Err(err @ EthereumError::WrongNetwork { .. }) => return Err(err),
+                Err(err) => {
+                    log::warn!(
+                        "batched ens resolution failed, resolving one by one: {}",
+                        err
+                    );
+                    for name in missing {
+                        self.resolve_ens(name).await?;
+                    }
+                }
+            }
+        }
+
+        let cache = self.ens_cache.borrow();
+        Ok(names
+            .iter()
+            .map(|name| cache.get(name).copied().flatten())
+            .collect())
+    }
+
+    async fn resolve_ens_uncached(
+        &self,
+        names: &[&String],
+    ) -> Result<Vec<Option<H160>>, EthereumError> {
+        let nodes: Vec<[u8; 32]> = names.iter().map(|name| ens::node(name)).collect();
+        let resolver_calls: Vec<Call3> = nodes
+            .iter()
+            .map(|node| Call3::new(ens::registry(), ens::resolver_call(*node)))
+            .collect();
+        let resolvers = self.ens_multicall(&resolver_calls).await?;
+        let resolvers = resolvers
+            .iter()
+            .map(|data| match data {
+                Some(data) => ens::decode_optional_address(data),
+                None => Ok(None),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let addr_calls: Vec<Call3> = nodes
+            .iter()
+            .zip(resolvers.iter())
+            .filter_map(|(node, resolver)| {
+                resolver.map(|resolver| Call3::new(resolver, ens::addr_call(*node)))
+            })
+            .collect();
+        let mut addresses = if addr_calls.is_empty() {
+            Vec::new()
+        } else {
+            self.ens_multicall(&addr_calls).await?
+        }
+        .into_iter();
+
+        resolvers
+            .iter()
+            .map(|resolver| match (resolver, addresses.next()) {
+                (Some(_), Some(Some(data))) => Ok(ens::decode_optional_address(&data)?),
+                _ => Ok(None),
+            })
+            .collect()
+    }
+
+    /// primary ENS name of `address`, `None` unless the reverse record is set and the name
+    /// resolves back to the address
+    pub async fn lookup_ens(&self, address: &H160) -> Result<Option<String>, EthereumError> {
+        let name = self.lookup_ens_uncached(address).await?;
+        self.ens_names.borrow_mut().insert(*address, name.clone());
+        Ok(name)
+    }
+
+    async fn lookup_ens_uncached(&self, address: &H160) -> Result<Option<String>, EthereumError> {
+        let node = ens::node(&ens::reverse_name(address));
+        let resolver = match self.ens_resolver(node).await? {
+            Some(resolver) => resolver,
+            None => return Ok(None),
+        };
+        let data = self.ens_call(resolver, ens::name_call(node)).await?;
+        if data.is_empty() {
+            return Ok(None);
+        }
+        let name = abi::decode_string(&data)?;
+        if name.is_empty() || self.resolve_ens(&name).await? != Some(*address) {
+            return Ok(None);
+        }
+        Ok(Some(name))
+    }
+
+    /// the connected account in `style`, empty while there is none
+    pub fn format_address(&self, style: AddressStyle) -> String {
         self.address()
-            .map(|address| address.to_string())
-            // .map(|address| format!("0x{}", &address.split_at(2).1))
+            .map(|address| {
+                let ens_names = self.ens_names.borrow();
+                let ens_name = ens_names.get(address).and_then(Option::as_deref);
+                crate::format_address(address, style, ens_name)
+            })
             .unwrap_or_default()
     }
 
+    /// the connected account as `0x1234…abcd`, the first and last 4 hex digits of its EIP-55
+    /// checksummed form
+    pub fn display_short_address(&self) -> String {
+        self.format_address(AddressStyle::Short { lead: 4, trail: 4 })
+    }
+
+    /// the connected account with its EIP-55 checksum, as explorers expect when pasted
     pub fn display_address(&self) -> String {
-        self.address()
-            .map(|add| format!("{:?}", add))
-            .unwrap_or(String::new())
+        self.format_address(AddressStyle::Checksummed)
+    }
+
+    /// EIP-55 mixed-case form of the connected account
+    pub fn checksum_address(&self) -> Option<String> {
+        self.address().map(crate::to_checksum)
     }
 
     pub async fn on_accounts_changed<F>(&self, callback: F)
     where
         F: Fn(Vec<web3::types::H160>),
     {
-        let transport = Eip1193::new(self.provider.clone());
+        let transport = match &self.transport {
+            Some(transport) => transport.clone(),
+            None => return,
+        };
         let mut stream = transport.accounts_changed_stream();
         while let Some(accounts) = stream.next().await {
             log::info!("accounts changed");
@@ -148,18 +1645,88 @@ impl UseEthereumHandle {
     where
         F: Fn(String),
     {
-        let transport = Eip1193::new(self.provider.clone());
+        let transport = match &self.transport {
+            Some(transport) => transport.clone(),
+            None => return,
+        };
         let mut stream = transport.chain_changed_stream();
         while let Some(chainid) = stream.next().await {
             callback(chainid.to_string());
         }
     }
 
+    /// the payload of every EIP-1193 `message` event, which is how wallets deliver
+    /// `eth_subscription` notifications for subscriptions started with `request`
+    pub async fn on_message<F>(&self, callback: F)
+    where
+        F: Fn(serde_json::Value),
+    {
+        let provider = match &self.provider {
+            Some(provider) => provider,
+            None => return,
+        };
+        let (sender, mut receiver) = mpsc::unbounded();
+        let listener = EventListener::new(provider, "message", move |message| {
+            let _ = sender.unbounded_send(message);
+        });
+        let _listener = match listener {
+            Ok(listener) => listener,
+            Err(err) => {
+                log::error!(
+                    "failed to listen for messages: {}",
+                    js_error_to_string(&err)
+                );
+                return;
+            }
+        };
+        while let Some(message) = receiver.next().await {
+            let json = js_sys::JSON::stringify(&message)
+                .ok()
+                .and_then(|json| json.as_string());
+            match json.map(|json| serde_json::from_str(&json)) {
+                Some(Ok(message)) => callback(message),
+                _ => log::error!("invalid message event: {}", js_error_to_string(&message)),
+            }
+        }
+    }
+
+    /// `eth_subscribe("newHeads")`, calling `callback` with every new block header until the
+    /// subscription ends. fails right away if the provider can't subscribe, as is the case in
+    /// read-only mode and for some injected wallets, so callers can fall back to polling.
+    /// dropping the future, eg. when the component awaiting it unmounts, sends `eth_unsubscribe`
+    pub async fn on_new_heads<F>(&self, callback: F) -> Result<(), EthereumError>
+    where
+        F: Fn(BlockHeader),
+    {
+        let transport = match &self.transport {
+            Some(transport) => transport.clone(),
+            None => return Err(EthereumError::Unsupported(String::from("eth_subscribe"))),
+        };
+        let mut stream = web3::Web3::new(transport)
+            .eth_subscribe()
+            .subscribe_new_heads()
+            .await?;
+        let _subscription = WalletSubscription {
+            ethereum: self.clone(),
+            id: json!(stream.id()),
+        };
+        while let Some(header) = stream.next().await {
+            match header {
+                Ok(header) => callback(header),
+                Err(err) => log::error!("invalid newHeads notification: {}", err),
+            }
+        }
+        Ok(())
+    }
+
     pub async fn on_connect<F>(&self, callback: F)
     where
         F: Fn(Option<String>),
     {
-        let transport = Eip1193::new(self.provider.clone());
+        let transport = match &self.transport {
+            Some(transport) => transport.clone(),
+            None => return,
+        };
         let mut stream = transport.connect_stream();
         while let Some(connect) = stream.next().await {
             callback(connect);
@@ -170,7 +1737,10 @@ impl UseEthereumHandle {
     where
         F: Fn(String),
     {
-        let transport = Eip1193::new(self.provider.clone());
+        let transport = match &self.transport {
+            Some(transport) => transport.clone(),
+            None => return,
+        };
         let mut stream = transport.disconnect_stream();
         while let Some(err) = stream.next().await {
             callback(err.to_string());
@@ -182,12 +1752,33 @@ impl UseEthereumHandle {
     /// # Arguments
     /// * `chain` - a `Chain` instance representing the target chain
     ///
-    pub async fn switch_chain_with_fallback(&self, chain: &Chain) -> Result<(), JsValue> {
-        self.add_chain(chain).await;
+    pub async fn switch_chain_with_fallback(&self, chain: &Chain) -> Result<(), EthereumError> {
+        let on_target = |current: Result<u64, EthereumError>| current.ok() == chain.id();
+        if on_target(self.current_chain_id().await) {
+            return Ok(());
+        }
+
+        if self.add_chain(chain).await? == AddChainOutcome::Rejected {
+            return Err(EthereumError::ChainSwitchRejected(
+                chain.id().unwrap_or_default(),
+            ));
+        }
+        // wallets usually offer to switch right after adding a chain
+        if on_target(self.current_chain_id().await) {
+            return Ok(());
+        }
+
         self.switch_chain(&chain.chain_id).await?;
         Ok(())
     }
 
+    /// `eth_chainId` as reported by the provider right now, rather than the last known
+    /// `chain_id()`
+    pub async fn current_chain_id(&self) -> Result<u64, EthereumError> {
+        let chain_id = self.request("eth_chainId", vec![]).await?;
+        Ok(web3::helpers::decode::<U256>(chain_id)?.as_u64())
+    }
+
     /**
      * EIP-3326: Switch a wallet to another chain
      * https://eips.ethereum.org/EIPS/eip-3326
@@ -195,64 +1786,441 @@ impl UseEthereumHandle {
      *
      * @param {number} chainId network chain identifier
      */
-    pub async fn switch_chain(&self, chain_id: &str) -> Result<JsValue, JsValue> {
+    /// fails with `ChainNotAdded` if the wallet doesn't know the chain, see `add_chain`
+    pub async fn switch_chain(&self, chain_id: &str) -> Result<(), EthereumError> {
         log::info!("switch_chain");
 
-        self
-            .request("wallet_switchEthereumChain", vec![json!({"chainId": chain_id})])
-            .await
-            .map(|_| JsValue::from(chain_id))
-            .map_err(|_| JsValue::from("error deserializing request params"))
+        self.request(
+            "wallet_switchEthereumChain",
+            vec![json!({"chainId": chain_id})],
+        )
+        .await?;
+        Ok(())
     }
 
     /// EIP-3085: Add a wallet to another chain
     /// - https://eips.ethereum.org/EIPS/eip-3085
     /// - https://docs.metamask.io/guide/rpc-api.html#wallet-addethereumchain
-    pub async fn add_chain(&self, chain: &Chain) -> Result<(), JsValue> {
+    ///
+    /// the wallet isn't prompted when it is already on `chain`
+    pub async fn add_chain(&self, chain: &Chain) -> Result<AddChainOutcome, EthereumError> {
         log::info!("add_chain");
 
-        self
+        if chain.id().is_some() && self.current_chain_id().await.ok() == chain.id() {
+            return Ok(AddChainOutcome::AlreadyExists);
+        }
+
+        // wallets answer `null` whether or not they already knew the chain
+        match self
             .request("wallet_addEthereumChain", vec![json!(&chain)])
             .await
-            .map(|_| ())
-            .map_err(|_| JsValue::from("error deserializing request params"))
+        {
+            Ok(_) => Ok(AddChainOutcome::Added),
+            Err(EthereumError::UserRejected) => Ok(AddChainOutcome::Rejected),
+            // metamask refuses to add its built-in chains ("May not specify default MetaMask chain")
+            Err(EthereumError::Rpc { message, .. })
+                if message.contains("already") || message.contains("default") =>
+            {
+                Ok(AddChainOutcome::AlreadyExists)
+            }
+            Err(err) => Err(err),
+        }
     }
 
-    pub async fn watch_asset(&self, asset: &ERC20Asset) -> Result<(), JsValue> {
+    /// `add_chain` returning the `chain::warnings` about `chain` along with the outcome, plus a
+    /// warning if its rpc endpoint reports another chain id. the chain is added regardless, show
+    /// the warnings of `chain::warnings` before calling this to let the user back out
+    pub async fn add_chain_checked(
+        &self,
+        chain: &Chain,
+    ) -> Result<(AddChainOutcome, Vec<ChainWarning>), EthereumError> {
+        let mut warnings = chain::warnings(chain);
+        let [rpc_url] = &chain.rpc_urls;
+        match Http::new(rpc_url) {
+            Ok(rpc) => match rpc.execute("eth_chainId", vec![]).await {
+                Ok(reported) => {
+                    let reported = web3::helpers::decode::<U256>(reported)?.as_u64();
+                    if Some(reported) != chain.id() {
+                        warnings.push(ChainWarning::RpcChainIdMismatch { reported });
+                    }
+                }
+                Err(err) => log::warn!("could not verify the chain id of {}: {}", rpc_url, err),
+            },
+            Err(err) => log::warn!("invalid rpc url {}: {}", rpc_url, err),
+        }
+        let outcome = self.add_chain(chain).await?;
+        Ok((outcome, warnings))
+    }
+
+    /// ask the wallet to track `asset`. fails with `InvalidRequest` without prompting if
+    /// `asset.validate()` finds a problem
+    pub async fn watch_asset(&self, asset: &ERC20Asset) -> Result<(), EthereumError> {
         log::info!("watch_asset");
+        let errors = asset.validate();
+        if !errors.is_empty() {
+            return Err(EthereumError::InvalidRequest(errors.join(", ")));
+        }
 
-        self
-            .request("wallet_watchAsset", vec![json!({
-                "r#type": String::from("ERC20"),
+        self.request(
+            "wallet_watchAsset",
+            vec![json!({
+                "type": String::from("ERC20"),
                 "options": asset
-            })])
-            .await
-            .map(|_| ())
-            .map_err(|_| JsValue::from("error deserializing request params"))
+            })],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// `watch_asset` of each of `assets` in turn, since wallets prompt for one asset at a time.
+    /// the results line up with `assets`, declined ones being `EthereumError::UserRejected`
+    pub async fn watch_assets(&self, assets: &[ERC20Asset]) -> Vec<Result<(), EthereumError>> {
+        let mut results = Vec::with_capacity(assets.len());
+        for asset in assets {
+            results.push(self.watch_asset(asset).await);
+        }
+        results
+    }
+
+    /// several json-rpc requests sent concurrently, since EIP-1193 has no batches. the results
+    /// are in the order of `calls` whatever order the answers arrive in
+    pub async fn batch(
+        &self,
+        calls: Vec<(&str, Vec<serde_json::Value>)>,
+    ) -> Vec<Result<serde_json::Value, EthereumError>> {
+        join_all(
+            calls
+                .into_iter()
+                .map(|(method, params)| self.request(method, params)),
+        )
+        .await
     }
 
-    pub async fn request(&self, method: &str, params: Vec<serde_json::Value>) -> web3::error::Result<serde_json::value::Value> {
-        let transport = Eip1193::new(self.provider.clone());
-        let (request_id, request) = transport.prepare(method, params);
-        transport.send(request_id, request).await
+    /// send a json-rpc request to the wallet, or to the rpc endpoint in read-only mode
+    pub async fn request(
+        &self,
+        method: &str,
+        params: Vec<serde_json::Value>,
+    ) -> Result<serde_json::Value, EthereumError> {
+        self.idle.borrow_mut().touch();
+        #[cfg(feature = "mock")]
+        self.mock_latency().await;
+        let middleware = self.middleware.borrow().clone();
+        if let Some(response) = middleware.intercept(method, &params) {
+            return Ok(response);
+        }
+        let read_rpc = if is_read_method(method) {
+            self.read_rpc().await
+        } else {
+            None
+        };
+        let response = if let Some(read_rpc) = read_rpc {
+            read_rpc.execute(method, params).await
+        } else if let Some(transport) = &self.transport {
+            let (request_id, request) = transport.prepare(method, params);
+            transport.send(request_id, request).await
+        } else if let Some(rpc) = &self.rpc {
+            rpc.execute(method, params).await
+        } else {
+            return Err(EthereumError::NoProvider);
+        }
+        .map_err(EthereumError::from);
+        let middleware = self.middleware.borrow().clone();
+        middleware.observe(method, &response);
+        response
     }
 }
 
+/// whether an rpc error says the node no longer has the state of the requested block, as
+/// reported by geth ("missing trie node", "header not found"), erigon and hosted providers
+fn is_missing_state(message: &str) -> bool {
+    let message = message.to_lowercase();
+    [
+        "missing trie node",
+        "header not found",
+        "state not available",
+        "pruned",
+        "archive",
+    ]
+    .iter()
+    .any(|pattern| message.contains(pattern))
+}
+
+#[cfg(feature = "mock")]
+impl UseEthereumHandle {
+    /// `handle` with `mock` in place of its wallet or rpc endpoint: requests no middleware
+    /// answers get the mock's canned responses, including `eth_requestAccounts` so `connect()`
+    /// adopts the mock's accounts, and fail with `NoProvider` for methods it has none for.
+    /// `clear_middleware` keeps the mock. see `use_mock_ethereum` for a fresh mocked handle
+    pub fn from_mock(handle: UseEthereumHandle, mock: MockProvider) -> UseEthereumHandle {
+        handle.middleware.borrow_mut().mock = Some(mock);
+        UseEthereumHandle {
+            provider: None,
+            transport: None,
+            rpc: None,
+            ..handle
+        }
+    }
+
+    /// wait out the `MockProvider::latency` of the mock backing this handle, if any
+    async fn mock_latency(&self) {
+        let latency = self
+            .middleware
+            .borrow()
+            .mock
+            .as_ref()
+            .map(|mock| mock.latency);
+        if let Some(latency) = latency.filter(|latency| !latency.is_zero()) {
+            sleep(latency).await;
+        }
+    }
+}
+
+/// whether `method` only reads chain state, so `request` can send it to the endpoint set with
+/// `set_read_rpc_url` instead of the wallet
+pub fn is_read_method(method: &str) -> bool {
+    matches!(
+        method,
+        "eth_call"
+            | "eth_getBalance"
+            | "eth_getLogs"
+            | "eth_blockNumber"
+            | "eth_getCode"
+            | "eth_getStorageAt"
+            | "eth_getBlockByNumber"
+            | "eth_getBlockByHash"
+            | "eth_getTransactionByHash"
+            | "eth_getTransactionReceipt"
+            | "eth_feeHistory"
+            | "eth_gasPrice"
+    )
+}
+
+/// whether a wallet is injected as `window.ethereum`. `use_ethereum` returns `None` without one,
+/// so apps can offer to install a wallet instead
+pub fn is_available() -> bool {
+    web_sys::window()
+        .and_then(|window| Reflect::get(&window, &JsValue::from("ethereum")).ok())
+        .map_or(false, |ethereum| {
+            !ethereum.is_undefined() && !ethereum.is_null()
+        })
+}
+
 #[hook]
 pub fn use_ethereum(default: Option<Provider>) -> Option<UseEthereumHandle> {
-    let connected = use_state(move || false);
-    let accounts = use_state(move || None as Option<Vec<H160>>);
-    let chain_id = use_state(move || None as Option<U256>);
-
-    if let Some(provider) = default.or(Provider::default().unwrap()) {
-        Some(UseEthereumHandle {
-            provider,
-            connected,
-            accounts,
-            chain_id,
+    let state = use_handle_state(None, None);
+
+    let injected = || {
+        Provider::default().unwrap_or_else(|err| {
+            log::error!(
+                "failed to read window.ethereum: {}",
+                js_error_to_string(&err)
+            );
+            None
         })
-    } else {
-        None
+    };
+
+    let provider = default.or_else(injected);
+    let transport = use_transport(provider.clone());
+
+    provider.map(|provider| UseEthereumHandle {
+        provider: Some(provider),
+        transport,
+        ..state
+    })
+}
+
+/// A plain json-rpc endpoint for reading chain state without a wallet
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReadOnlyConfig {
+    pub rpc_url: String,
+    /// chain served by `rpc_url`, reported by `chain_id()` since there is no wallet to ask
+    pub chain_id: u64,
+}
+
+/// a handle that reads through `config.rpc_url` instead of a wallet. returns None without a
+/// config or if the url is invalid. the endpoint's `eth_chainId` is checked against
+/// `config.chain_id` once and a warning logged if they differ
+#[hook]
+pub fn use_read_only_ethereum(config: Option<ReadOnlyConfig>) -> Option<UseEthereumHandle> {
+    let configured = config.as_ref().map(|config| U256::from(config.chain_id));
+    let state = use_handle_state(None, configured);
+    let rpc = use_memo(
+        |config| {
+            config.as_ref().and_then(|config| {
+                Http::new(&config.rpc_url)
+                    .map_err(|err| log::error!("invalid rpc url {}: {}", config.rpc_url, err))
+                    .ok()
+            })
+        },
+        config.clone(),
+    );
+
+    let handle = (*rpc).clone().map(|rpc| UseEthereumHandle {
+        rpc: Some(rpc),
+        ..state
+    });
+
+    {
+        let handle = handle.clone();
+        use_effect_with_deps(
+            move |config| {
+                if let (Some(handle), Some(config)) = (handle, config.clone()) {
+                    handle.set_chain_id(Some(U256::from(config.chain_id)));
+                    spawn_local(async move {
+                        let reported = handle.current_chain_id().await;
+                        match reported {
+                            Ok(reported) if reported != config.chain_id => {
+                                log::warn!(
+                                    "{} serves chain {} but was configured as chain {}",
+                                    config.rpc_url,
+                                    reported,
+                                    config.chain_id
+                                )
+                            }
+                            Ok(_) => (),
+                            Err(err) => log::warn!(
+                                "could not verify the chain id of {}: {}",
+                                config.rpc_url,
+                                err
+                            ),
+                        }
+                    });
+                }
+                || ()
+            },
+            config,
+        );
+    }
+
+    handle
+}
+
+/// a handle backed by `mock` instead of a wallet, connected with its accounts (if any) on its
+/// chain. see `UseEthereumHandle::from_mock`. `mock` is read on the first render only
+#[cfg(feature = "mock")]
+#[hook]
+pub fn use_mock_ethereum(mock: MockProvider) -> UseEthereumHandle {
+    let accounts = Some(mock.accounts.clone()).filter(|accounts| !accounts.is_empty());
+    let state = use_handle_state(accounts, Some(U256::from(mock.chain_id)));
+    let mock = use_memo(|_| mock, ());
+    UseEthereumHandle::from_mock(state, (*mock).clone())
+}
+
+/// the state shared by every kind of handle, with neither a wallet nor an rpc endpoint yet.
+/// `accounts` start out connected
+#[hook]
+fn use_handle_state(accounts: Option<Vec<H160>>, chain_id: Option<U256>) -> UseEthereumHandle {
+    let connected = use_state(|| accounts.is_some());
+    let accounts = use_state(move || accounts);
+    let selected_account = use_state(|| 0usize);
+    let chain_id = use_state(move || chain_id);
+    let connect_pending = use_state(|| false);
+    let last_error = use_state(|| None as Option<EthereumError>);
+    let idle = use_idle_state();
+    let connecting = use_mut_ref(PendingConnection::default);
+    let persist = use_mut_ref(|| false);
+    let js_listeners = use_js_listeners();
+    let subscriptions = use_subscriptions();
+    let middleware = use_mut_ref(Middleware::default);
+    let debounce = use_mut_ref(EventDebounce::default);
+    let read_rpc = use_mut_ref(|| None as Option<ReadRpc>);
+    let ens_rpc = use_mut_ref(|| None as Option<Http>);
+    let ens_cache = use_mut_ref(HashMap::new);
+    let ens_names = use_mut_ref(HashMap::new);
+
+    UseEthereumHandle {
+        provider: None,
+        transport: None,
+        rpc: None,
+        connected,
+        accounts,
+        selected_account,
+        chain_id,
+        connect_pending,
+        last_error,
+        idle,
+        connecting,
+        persist,
+        js_listeners,
+        subscriptions,
+        middleware,
+        debounce,
+        read_rpc,
+        ens_rpc,
+        ens_cache,
+        ens_names,
+    }
+}
+
+/// an `Eip1193` transport over `provider`, kept across renders for as long as the provider
+/// stays the same object
+#[hook]
+fn use_transport(provider: Option<Provider>) -> Option<Eip1193> {
+    let cached = use_mut_ref(|| None as Option<(Provider, Eip1193)>);
+    let mut cached = cached.borrow_mut();
+    let provider = match provider {
+        Some(provider) => provider,
+        None => {
+            *cached = None;
+            return None;
+        }
+    };
+    let stale = match &*cached {
+        Some((cached, _)) => {
+            let (cached, provider): (&JsValue, &JsValue) = (cached, &provider);
+            cached != provider
+        }
+        None => true,
+    };
+    if stale {
+        *cached = Some((provider.clone(), Eip1193::new(provider)));
+    }
+    cached.as_ref().map(|(_, transport)| transport.clone())
+}
+
+/// idle bookkeeping that lives as long as the calling component, stopping any idle watcher when
+/// it unmounts
+#[hook]
+fn use_idle_state() -> Rc<RefCell<IdleState>> {
+    let idle = use_mut_ref(IdleState::default);
+    {
+        let idle = idle.clone();
+        use_effect_with_deps(
+            move |_| {
+                move || {
+                    let mut idle = idle.borrow_mut();
+                    idle.generation = idle.generation.wrapping_add(1);
+                }
+            },
+            (),
+        );
+    }
+    idle
+}
+
+/// wallet event loops of the calling component, aborted when it unmounts rather than left
+/// running against a provider nothing renders anymore
+#[hook]
+fn use_subscriptions() -> Rc<RefCell<Vec<Subscription>>> {
+    let subscriptions = use_mut_ref(Vec::new);
+    {
+        let subscriptions = subscriptions.clone();
+        use_effect_with_deps(move |_| move || subscriptions.borrow_mut().clear(), ());
+    }
+    subscriptions
+}
+
+/// javascript listeners of the calling component, released when it unmounts
+#[hook]
+fn use_js_listeners() -> Rc<RefCell<JsListeners>> {
+    let listeners = use_mut_ref(JsListeners::default);
+    {
+        let listeners = listeners.clone();
+        use_effect_with_deps(
+            move |_| move || *listeners.borrow_mut() = JsListeners::default(),
+            (),
+        );
     }
-    
+    listeners
 }
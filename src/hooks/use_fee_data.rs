@@ -0,0 +1,26 @@
+use super::{use_poll, UseEthereumHandle};
+use crate::{EthereumError, FeeLevel, FeeSuggestion, DEFAULT_BLOCK_TIME_SECS};
+use yew::prelude::*;
+
+/// EIP-1559 fees for `level` on the current chain, see `UseEthereumHandle::suggest_fees`.
+/// suggested again about every block and whenever the chain or level changes. `None` until
+/// suggested
+#[hook]
+pub fn use_fee_data(ethereum: Option<UseEthereumHandle>, level: FeeLevel) -> Option<FeeSuggestion> {
+    let chain_id = ethereum.as_ref().and_then(UseEthereumHandle::chain_id);
+
+    use_poll(
+        move || {
+            let ethereum = ethereum.clone();
+            async move {
+                match ethereum {
+                    Some(ethereum) => ethereum.suggest_fees(level).await.map(Some),
+                    None => Ok::<_, EthereumError>(None),
+                }
+            }
+        },
+        Some((DEFAULT_BLOCK_TIME_SECS * 1000.0) as u32),
+        (chain_id, level),
+    )
+    .flatten()
+}
@@ -0,0 +1,26 @@
+use super::{use_poll, UseEthereumHandle};
+use crate::{EthereumError, DEFAULT_BLOCK_TIME_SECS};
+use web3::types::U256;
+use yew::prelude::*;
+
+/// legacy `eth_gasPrice` of the current chain in wei, read again about every block and
+/// whenever the chain changes. `None` until read
+#[hook]
+pub fn use_gas_price(ethereum: Option<UseEthereumHandle>) -> Option<U256> {
+    let chain_id = ethereum.as_ref().and_then(UseEthereumHandle::chain_id);
+
+    use_poll(
+        move || {
+            let ethereum = ethereum.clone();
+            async move {
+                match ethereum {
+                    Some(ethereum) => ethereum.gas_price().await.map(Some),
+                    None => Ok::<_, EthereumError>(None),
+                }
+            }
+        },
+        Some((DEFAULT_BLOCK_TIME_SECS * 1000.0) as u32),
+        chain_id,
+    )
+    .flatten()
+}
@@ -0,0 +1,102 @@
+use std::{cell::Cell, fmt::Display, future::Future, rc::Rc, time::Duration};
+use yew::{
+    platform::{spawn_local, time::sleep},
+    prelude::*,
+};
+
+/// State of a poll started with `use_poll_with_refetch`
+#[derive(Clone, PartialEq)]
+pub struct UsePollHandle<T> {
+    /// the latest value fetched, see `use_poll`
+    pub value: Option<T>,
+    /// fetch again right away and restart the interval, keeping the current value until then
+    pub refetch: Callback<()>,
+}
+
+/// the latest value of `fetch`, called on mount, whenever `deps` change and then every
+/// `interval_ms` milliseconds (or only once with `None`). `None` until the first fetch
+/// succeeds and again after `deps` change, failed fetches are logged and keep the previous
+/// value. polling stops when the component unmounts or `deps` change. the closure of the
+/// latest render is the one polled
+#[hook]
+pub fn use_poll<T, E, F, Fut, D>(fetch: F, interval_ms: Option<u32>, deps: D) -> Option<T>
+where
+    T: Clone + 'static,
+    E: Display + 'static,
+    F: Fn() -> Fut + 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+    D: PartialEq + 'static,
+{
+    use_poll_with_refetch(fetch, interval_ms, deps).value
+}
+
+/// `use_poll` that can also be told to fetch again, eg. once a transaction is confirmed
+#[hook]
+pub fn use_poll_with_refetch<T, E, F, Fut, D>(
+    fetch: F,
+    interval_ms: Option<u32>,
+    deps: D,
+) -> UsePollHandle<T>
+where
+    T: Clone + 'static,
+    E: Display + 'static,
+    F: Fn() -> Fut + 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+    D: PartialEq + 'static,
+{
+    let value = use_state(|| None as Option<T>);
+    let refetches = use_state(|| 0u32);
+    let last_refetch = use_mut_ref(|| 0u32);
+    let fetch = {
+        let fetch_ref = use_mut_ref(|| None as Option<Rc<F>>);
+        *fetch_ref.borrow_mut() = Some(Rc::new(fetch));
+        fetch_ref
+    };
+
+    {
+        let value = value.clone();
+        use_effect_with_deps(
+            move |(_, interval_ms, refetch)| {
+                // a refetch keeps showing the value it is replacing
+                if *last_refetch.borrow() == *refetch {
+                    value.set(None);
+                }
+                *last_refetch.borrow_mut() = *refetch;
+                let interval_ms = *interval_ms;
+                // cleared when the deps change or the component unmounts, ending the loop
+                let active = Rc::new(Cell::new(true));
+                {
+                    let active = active.clone();
+                    spawn_local(async move {
+                        while active.get() {
+                            let fetch = match fetch.borrow().clone() {
+                                Some(fetch) => fetch,
+                                None => return,
+                            };
+                            let result = fetch().await;
+                            if !active.get() {
+                                return;
+                            }
+                            match result {
+                                Ok(fetched) => value.set(Some(fetched)),
+                                Err(err) => log::error!("poll failed: {}", err),
+                            }
+                            match interval_ms {
+                                Some(ms) => sleep(Duration::from_millis(ms as u64)).await,
+                                None => return,
+                            }
+                        }
+                    });
+                }
+                move || active.set(false)
+            },
+            (deps, interval_ms, *refetches),
+        );
+    }
+
+    let refetch = Callback::from(move |_| refetches.set(*refetches + 1));
+    UsePollHandle {
+        value: (*value).clone(),
+        refetch,
+    }
+}
@@ -0,0 +1,56 @@
+use super::UseEthereumHandle;
+use crate::Chain;
+use yew::{platform::spawn_local, prelude::*};
+
+/// how long `use_require_chain` waits after a declined or failed switch before prompting again
+pub const REQUIRE_CHAIN_COOLDOWN_MS: f64 = 30_000.0;
+
+#[derive(Default)]
+struct Prompt {
+    in_flight: bool,
+    /// `js_sys::Date::now()` of the last switch that didn't go through
+    failed_at: Option<f64>,
+}
+
+/// whether the connected wallet is on `chain`. whenever it isn't, the user is prompted to
+/// switch with `switch_chain_with_fallback`, at most once per `REQUIRE_CHAIN_COOLDOWN_MS`
+/// after declining so they aren't prompted over and over
+#[hook]
+pub fn use_require_chain(ethereum: Option<UseEthereumHandle>, chain: Chain) -> bool {
+    let connected = ethereum
+        .as_ref()
+        .map_or(false, UseEthereumHandle::connected);
+    let chain_id = ethereum.as_ref().and_then(UseEthereumHandle::chain_id);
+    let on_chain = connected && chain_id.is_some() && chain_id == chain.id();
+    let prompt = use_mut_ref(Prompt::default);
+
+    use_effect_with_deps(
+        move |(chain, connected, on_chain, _)| {
+            let cooling_down = prompt.borrow().failed_at.map_or(false, |failed_at| {
+                js_sys::Date::now() - failed_at < REQUIRE_CHAIN_COOLDOWN_MS
+            });
+            if let (Some(ethereum), true, false) = (ethereum, *connected, *on_chain) {
+                if !prompt.borrow().in_flight && !cooling_down {
+                    prompt.borrow_mut().in_flight = true;
+                    let chain = chain.clone();
+                    spawn_local(async move {
+                        let result = ethereum.switch_chain_with_fallback(&chain).await;
+                        let mut prompt = prompt.borrow_mut();
+                        prompt.in_flight = false;
+                        prompt.failed_at = match result {
+                            Ok(()) => None,
+                            Err(err) => {
+                                log::warn!("switch to {} failed: {}", chain.chain_name, err);
+                                Some(js_sys::Date::now())
+                            }
+                        };
+                    });
+                }
+            }
+            || ()
+        },
+        (chain, connected, on_chain, chain_id),
+    );
+
+    on_chain
+}
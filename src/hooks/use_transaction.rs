@@ -0,0 +1,60 @@
+use super::{use_async_action, UseEthereumHandle};
+use crate::{EthereumError, TransactionReceipt};
+use std::future::Future;
+use web3::types::H256;
+use yew::prelude::*;
+
+/// State of the transaction sent with `use_transaction`
+#[derive(Clone, Debug)]
+pub struct UseTransactionHandle {
+    /// send the transaction and wait for it to be mined. ignored while one is pending
+    pub send: Callback<()>,
+    /// whether the wallet is still sending the transaction or it is waiting to be mined
+    pub pending: bool,
+    /// hash of the last transaction, once the wallet has broadcast it
+    pub hash: Option<H256>,
+    /// receipt of the last transaction mined
+    pub receipt: Option<TransactionReceipt>,
+    /// why the last transaction failed, cleared when a new one is sent. a reverted transaction
+    /// is an `EthereumError::TransactionReverted`
+    pub error: Option<EthereumError>,
+}
+
+/// sends the transaction `send` broadcasts, returning its hash, and waits until it is mined and
+/// buried under `confirmations` blocks (counting its own). the closure of the latest render is
+/// the one invoked
+#[hook]
+pub fn use_transaction<F, Fut>(
+    ethereum: Option<UseEthereumHandle>,
+    confirmations: u64,
+    send: F,
+) -> UseTransactionHandle
+where
+    F: Fn() -> Fut + 'static,
+    Fut: Future<Output = Result<H256, EthereumError>> + 'static,
+{
+    let hash = use_state(|| None as Option<H256>);
+    let action = {
+        let hash = hash.clone();
+        use_async_action(move || {
+            hash.set(None);
+            let ethereum = ethereum.clone();
+            let hash = hash.clone();
+            let sent = send();
+            async move {
+                let ethereum = ethereum.ok_or(EthereumError::NotConnected)?;
+                let sent = sent.await?;
+                hash.set(Some(sent));
+                ethereum.wait_for_receipt(sent, confirmations).await
+            }
+        })
+    };
+
+    UseTransactionHandle {
+        send: action.run,
+        pending: action.pending,
+        hash: *hash,
+        receipt: action.result,
+        error: action.error,
+    }
+}
@@ -0,0 +1,22 @@
+use crate::{UseEthereumHandle, WalletInfo};
+use yew::prelude::*;
+
+/// the wallet `ethereum` is connected through, updated when it reconnects. `None` while
+/// disconnected and in read-only mode
+#[hook]
+pub fn use_wallet_info(ethereum: Option<UseEthereumHandle>) -> Option<WalletInfo> {
+    let connected = ethereum
+        .as_ref()
+        .map_or(false, UseEthereumHandle::connected);
+    let address = ethereum
+        .as_ref()
+        .and_then(|ethereum| ethereum.address().copied());
+    let info = use_memo(
+        move |(connected, _)| match ethereum {
+            Some(ethereum) if *connected => ethereum.wallet_info(),
+            _ => None,
+        },
+        (connected, address),
+    );
+    (*info).clone()
+}
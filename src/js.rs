@@ -0,0 +1,117 @@
+use js_sys::{Array, Function, Reflect, JSON};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web3::types::{H160, U256};
+
+/// a readable description of a javascript error value.
+///
+/// `JsValue`s thrown by providers are rarely strings, and formatting an object naively gives
+/// `[object Object]`. strings are returned as is, `Error`s and error-like objects as their
+/// `message` (plus `code` when present) and any other object as its json serialization.
+pub fn js_error_to_string(value: &JsValue) -> String {
+    if let Some(string) = value.as_string() {
+        return string;
+    }
+    if !value.is_object() {
+        return format!("{:?}", value);
+    }
+
+    let property = |name: &str| {
+        Reflect::get(value, &JsValue::from_str(name))
+            .ok()
+            .filter(|property| !property.is_undefined() && !property.is_null())
+    };
+    let message = match value.dyn_ref::<js_sys::Error>() {
+        Some(error) => error.message().as_string(),
+        None => property("message").and_then(|message| message.as_string()),
+    };
+    let code = property("code").map(|code| match code.as_f64() {
+        Some(number) => number.to_string(),
+        None => code.as_string().unwrap_or_else(|| format!("{:?}", code)),
+    });
+
+    match (message, code) {
+        (Some(message), Some(code)) => format!("{} (code {})", message, code),
+        (Some(message), None) => message,
+        _ => JSON::stringify(value)
+            .ok()
+            .and_then(|json| json.as_string())
+            .unwrap_or_else(|| format!("{:?}", value)),
+    }
+}
+
+/// Javascript functions observing the state of a `UseEthereumHandle`, for apps that embed the
+/// components in a larger javascript frontend
+#[derive(Debug, Default)]
+pub(crate) struct JsListeners {
+    pub accounts_changed: Option<Function>,
+    pub chain_changed: Option<Function>,
+}
+
+impl JsListeners {
+    /// call the accounts listener with an array of `0x`-prefixed addresses
+    pub fn accounts_changed(&self, accounts: &[H160]) {
+        if let Some(callback) = &self.accounts_changed {
+            let accounts: Array = accounts
+                .iter()
+                .map(|account| JsValue::from_str(&format!("{:?}", account)))
+                .collect();
+            if let Err(err) = callback.call1(&JsValue::NULL, &accounts) {
+                log::error!(
+                    "accountsChanged listener threw: {}",
+                    js_error_to_string(&err)
+                );
+            }
+        }
+    }
+
+    /// call the chain listener with the `0x`-prefixed hex chain id
+    pub fn chain_changed(&self, chain_id: Option<U256>) {
+        if let Some(callback) = &self.chain_changed {
+            let chain_id = chain_id
+                .map(|chain_id| JsValue::from_str(&format!("0x{:x}", chain_id)))
+                .unwrap_or(JsValue::NULL);
+            if let Err(err) = callback.call1(&JsValue::NULL, &chain_id) {
+                log::error!("chainChanged listener threw: {}", js_error_to_string(&err));
+            }
+        }
+    }
+}
+
+/// A listener registered with `provider.on(event, ...)`, removed again when dropped
+pub(crate) struct EventListener {
+    target: JsValue,
+    event: &'static str,
+    closure: Closure<dyn FnMut(JsValue)>,
+}
+
+impl EventListener {
+    pub fn new<F>(target: &JsValue, event: &'static str, callback: F) -> Result<Self, JsValue>
+    where
+        F: FnMut(JsValue) + 'static,
+    {
+        let closure = Closure::<dyn FnMut(JsValue)>::new(callback);
+        Reflect::get(target, &JsValue::from_str("on"))?
+            .dyn_into::<Function>()?
+            .call2(target, &JsValue::from_str(event), closure.as_ref())?;
+        Ok(EventListener {
+            target: target.clone(),
+            event,
+            closure,
+        })
+    }
+}
+
+impl Drop for EventListener {
+    fn drop(&mut self) {
+        let remove = Reflect::get(&self.target, &JsValue::from_str("removeListener"))
+            .ok()
+            .and_then(|remove| remove.dyn_into::<Function>().ok());
+        if let Some(remove) = remove {
+            let _ = remove.call2(
+                &self.target,
+                &JsValue::from_str(self.event),
+                self.closure.as_ref(),
+            );
+        }
+    }
+}
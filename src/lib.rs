@@ -1,14 +1,77 @@
 //! Yew components for metamask and other eip1193 clients
 
+mod address;
+pub use address::*;
+
 mod components;
 pub use components::*;
 
 mod hooks;
 pub use hooks::*;
 
+mod error;
+pub use error::*;
+
+mod cache;
+pub use cache::*;
+
+mod transaction;
+pub use transaction::*;
+
+mod fees;
+pub use fees::*;
+
+mod logs;
+pub use logs::*;
+
+mod client_version;
+pub use client_version::*;
+
+mod typed_data;
+pub use typed_data::*;
+
+mod js;
+pub use js::*;
+
+mod middleware;
+pub use middleware::*;
+
+mod permit;
+pub use permit::*;
+
+mod siwe;
+pub use siwe::*;
+
+mod storage;
+
+#[cfg(test)]
+mod test_util;
+
+mod wallet_info;
+pub use wallet_info::*;
+
+#[cfg(feature = "mock")]
+mod mock;
+#[cfg(feature = "mock")]
+pub use mock::*;
+
+#[cfg(feature = "signing")]
+mod recover;
+#[cfg(feature = "signing")]
+pub use recover::*;
+
+pub mod abi;
+
 pub mod base_currency;
 pub mod chain;
-
+pub mod codes;
+pub mod ens;
+pub mod erc165;
+pub mod multicall;
+pub mod rlp;
+pub mod time;
+pub mod units;
+pub mod wallet_ext;
 
 /// A descriptor for an ethereum-compatible chain
 #[derive(serde::Serialize, Default, PartialEq, Clone)]
@@ -28,6 +91,31 @@ pub struct Chain {
     pub block_explorer_urls: Option<[String; 1]>,
 }
 
+impl Chain {
+    /// the chain id as a decimal. returns None if `chain_id` is not valid hex
+    pub fn id(&self) -> Option<u64> {
+        u64::from_str_radix(self.chain_id.trim_start_matches("0x"), 16).ok()
+    }
+
+    /// link to a transaction on the chain's block explorer
+    pub fn explorer_tx_url(&self, hash: &web3::types::H256) -> Option<String> {
+        self.block_explorer_urls
+            .as_ref()
+            .map(|[url]| format!("{}/tx/{:?}", url.trim_end_matches('/'), hash))
+    }
+}
+
+/// What `add_chain` did with the chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddChainOutcome {
+    /// the wallet added the chain
+    Added,
+    /// the wallet already knew the chain
+    AlreadyExists,
+    /// the user declined adding the chain
+    Rejected,
+}
+
 /// Metadata for an ERC20 asset.
 #[derive(serde::Serialize, Default, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -42,6 +130,31 @@ pub struct ERC20Asset {
     pub image_url: String,
 }
 
+impl ERC20Asset {
+    /// every problem wallets would reject the asset for, each naming its field. empty if it
+    /// can be passed to `watch_asset`
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let hex = self.address.strip_prefix("0x").unwrap_or_default();
+        if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            errors.push(format!(
+                "address {:?} is not a 0x-prefixed 20 byte hex string",
+                self.address
+            ));
+        }
+        if self.decimals > 36 {
+            errors.push(format!("decimals {} is more than 36", self.decimals));
+        }
+        if self.token_symbol.is_empty() || self.token_symbol.chars().count() > 11 {
+            errors.push(format!(
+                "symbol {:?} must be 1 to 11 characters long",
+                self.token_symbol
+            ));
+        }
+        errors
+    }
+}
+
 /// A base currency for en ethereum compatible chain
 #[derive(serde::Serialize, Default, PartialEq, Clone)]
 pub struct BaseCurrency {
@@ -51,4 +164,51 @@ pub struct BaseCurrency {
     pub symbol: String, // 2-6 characters long
     /// number of decimal places (usually 8)
     pub decimals: u32,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn gnosis() -> Chain {
+        Chain {
+            chain_id: String::from("0x64"),
+            chain_name: String::from("Gnosis"),
+            rpc_urls: [String::from("https://rpc.gnosischain.com")],
+            native_currency: BaseCurrency {
+                name: String::from("xDai"),
+                symbol: String::from("XDAI"),
+                decimals: 18,
+            },
+            block_explorer_urls: Some([String::from("https://blockscout.com/poa/xdai/")]),
+        }
+    }
+
+    #[test]
+    fn chain_serializes_as_eip3085() {
+        // the `wallet_addEthereumChain` example of the MetaMask docs, less its icon urls
+        let expected = json!({
+            "chainId": "0x64",
+            "chainName": "Gnosis",
+            "rpcUrls": ["https://rpc.gnosischain.com"],
+            "nativeCurrency": {
+                "name": "xDai",
+                "symbol": "XDAI",
+                "decimals": 18
+            },
+            "blockExplorerUrls": ["https://blockscout.com/poa/xdai/"]
+        });
+        assert_eq!(serde_json::to_value(gnosis()).unwrap(), expected);
+    }
+
+    #[test]
+    fn chain_without_explorer_omits_the_key() {
+        let chain = Chain {
+            block_explorer_urls: None,
+            ..gnosis()
+        };
+        let value = serde_json::to_value(chain).unwrap();
+        assert!(value.get("blockExplorerUrls").is_none());
+    }
+}
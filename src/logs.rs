@@ -0,0 +1,121 @@
+use serde::{Serialize, Serializer};
+use web3::types::{H160, H256};
+
+/// A block to query state or logs at, either a height or one of the named tags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlockTag {
+    Number(u64),
+    #[default]
+    Latest,
+    Earliest,
+    Pending,
+}
+
+impl From<u64> for BlockTag {
+    fn from(number: u64) -> Self {
+        BlockTag::Number(number)
+    }
+}
+
+impl Serialize for BlockTag {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            BlockTag::Number(number) => serializer.serialize_str(&format!("0x{:x}", number)),
+            BlockTag::Latest => serializer.serialize_str("latest"),
+            BlockTag::Earliest => serializer.serialize_str("earliest"),
+            BlockTag::Pending => serializer.serialize_str("pending"),
+        }
+    }
+}
+
+/// Filter for `eth_getLogs`, serialized in the json-rpc shape
+#[derive(Serialize, Default, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<BlockTag>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<BlockTag>,
+    /// contracts emitting the logs, any of them matches. empty matches every contract
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub address: Vec<H160>,
+    /// one entry per topic position, each either `None` (wildcard) or a list of alternatives
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<Option<Vec<H256>>>,
+}
+
+/// `Transfer(address,address,uint256)`, the topic of ERC20 and ERC721 transfer events
+pub fn transfer_topic() -> H256 {
+    H256::from(web3::signing::keccak256(
+        b"Transfer(address,address,uint256)",
+    ))
+}
+
+/// an address as an indexed topic value
+pub fn address_topic(address: &H160) -> H256 {
+    H256::from(crate::abi::encode_address(address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+    use serde_json::json;
+
+    #[test]
+    fn block_tags() {
+        assert_eq!(json!(BlockTag::Number(0)), json!("0x0"));
+        assert_eq!(json!(BlockTag::Number(1_000_000)), json!("0xf4240"));
+        assert_eq!(json!(BlockTag::Latest), json!("latest"));
+        assert_eq!(json!(BlockTag::Earliest), json!("earliest"));
+        assert_eq!(json!(BlockTag::Pending), json!("pending"));
+    }
+
+    #[test]
+    fn empty_filter_skips_every_field() {
+        assert_eq!(json!(LogFilter::default()), json!({}));
+    }
+
+    #[test]
+    fn transfers_to_an_address() {
+        let token = H160::repeat_byte(0xaa);
+        let receiver = H160::repeat_byte(0xbb);
+        let filter = LogFilter {
+            from_block: Some(BlockTag::Number(16)),
+            to_block: Some(BlockTag::Latest),
+            address: vec![token],
+            topics: vec![
+                Some(vec![transfer_topic()]),
+                None,
+                Some(vec![address_topic(&receiver)]),
+            ],
+        };
+        assert_eq!(
+            json!(filter),
+            json!({
+                "fromBlock": "0x10",
+                "toBlock": "latest",
+                "address": [token],
+                "topics": [
+                    ["0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"],
+                    null,
+                    ["0x000000000000000000000000bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"],
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn topic_helpers() {
+        assert_eq!(
+            transfer_topic().as_bytes(),
+            &hex("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef")[..]
+        );
+        let address = H160::repeat_byte(0x01);
+        assert_eq!(&address_topic(&address).as_bytes()[..12], &[0; 12]);
+        assert_eq!(
+            &address_topic(&address).as_bytes()[12..],
+            address.as_bytes()
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use crate::EthereumError;
+#[cfg(feature = "mock")]
+use crate::MockProvider;
+use serde_json::Value;
+use std::{fmt, rc::Rc};
+
+/// Sees every request before it is sent, with its method and params. returning a value answers
+/// the request with it without reaching the transport, eg. to serve from a cache or to mock
+pub type RequestMiddleware = Rc<dyn Fn(&str, &[Value]) -> Option<Value>>;
+
+/// Sees the outcome of every request that reached the transport, with its method
+pub type ResponseMiddleware = Rc<dyn Fn(&str, &Result<Value, EthereumError>)>;
+
+/// Middleware registered on a `UseEthereumHandle`, run in the order it was added. requests run
+/// a clone of it, so middleware can add or clear middleware while it runs
+#[derive(Clone, Default)]
+pub(crate) struct Middleware {
+    pub requests: Vec<RequestMiddleware>,
+    pub responses: Vec<ResponseMiddleware>,
+    /// answers what no request middleware did, set by `UseEthereumHandle::from_mock`
+    #[cfg(feature = "mock")]
+    pub mock: Option<MockProvider>,
+}
+
+impl fmt::Debug for Middleware {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Middleware")
+            .field("requests", &self.requests.len())
+            .field("responses", &self.responses.len())
+            .finish()
+    }
+}
+
+impl Middleware {
+    /// the answer of the first request middleware that short-circuits `method`
+    pub fn intercept(&self, method: &str, params: &[Value]) -> Option<Value> {
+        let response = self
+            .requests
+            .iter()
+            .find_map(|middleware| middleware(method, params));
+        #[cfg(feature = "mock")]
+        let response = response.or_else(|| self.mock.as_ref()?.response(method));
+        response
+    }
+
+    pub fn observe(&self, method: &str, response: &Result<Value, EthereumError>) {
+        for middleware in &self.responses {
+            middleware(method, response);
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use serde_json::{json, Value};
+use std::{collections::HashMap, time::Duration};
+use web3::types::H160;
+
+/// Canned wallet state and rpc responses for testing components without a wallet, see
+/// `use_mock_ethereum`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MockProvider {
+    /// accounts the mock wallet is connected with, none to start disconnected
+    pub accounts: Vec<H160>,
+    pub chain_id: u64,
+    responses: HashMap<String, Value>,
+    pub(crate) latency: Duration,
+}
+
+impl MockProvider {
+    pub fn new(accounts: Vec<H160>, chain_id: u64) -> MockProvider {
+        MockProvider {
+            accounts,
+            chain_id,
+            responses: HashMap::new(),
+            latency: Duration::ZERO,
+        }
+    }
+
+    /// answer every `method` request with `response`, whatever its params
+    pub fn respond(mut self, method: impl Into<String>, response: Value) -> Self {
+        self.responses.insert(method.into(), response);
+        self
+    }
+
+    /// answer every request after `latency` rather than right away, like a wallet waiting on
+    /// the user
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// the canned response to `method`. `eth_accounts`, `eth_requestAccounts` and
+    /// `eth_chainId` default to the preset accounts and chain id
+    pub fn response(&self, method: &str) -> Option<Value> {
+        if let Some(response) = self.responses.get(method) {
+            return Some(response.clone());
+        }
+        match method {
+            "eth_accounts" | "eth_requestAccounts" => Some(json!(self.accounts)),
+            "eth_chainId" => Some(json!(format!("0x{:x}", self.chain_id))),
+            _ => None,
+        }
+    }
+}
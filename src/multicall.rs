@@ -0,0 +1,93 @@
+//! Batching of contract reads into a single `eth_call` through
+//! [Multicall3](https://github.com/mds1/multicall), deployed at the same address on most chains
+use crate::abi::{self, DecodeError, ParamType, Token};
+use web3::types::H160;
+
+/// address of the Multicall3 contract
+pub fn address() -> H160 {
+    H160::from_slice(&[
+        0xca, 0x11, 0xbd, 0xe0, 0x59, 0x77, 0xb3, 0x63, 0x11, 0x67, 0x02, 0x88, 0x62, 0xbe, 0x2a,
+        0x17, 0x39, 0x76, 0xca, 0x11,
+    ])
+}
+
+/// One call of an `aggregate3` batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call3 {
+    pub target: H160,
+    /// when false a revert of this call reverts the whole batch
+    pub allow_failure: bool,
+    pub call_data: Vec<u8>,
+}
+
+impl Call3 {
+    /// a call whose failure doesn't affect the rest of the batch
+    pub fn new(target: H160, call_data: Vec<u8>) -> Call3 {
+        Call3 {
+            target,
+            allow_failure: true,
+            call_data,
+        }
+    }
+}
+
+/// a `getEthBalance(address)` call on Multicall3 itself, reading the native balance of
+/// `account` as a `uint256`
+pub fn get_eth_balance_call(account: &H160) -> Call3 {
+    Call3::new(
+        address(),
+        abi::encode_call(
+            abi::selector("getEthBalance(address)"),
+            &[abi::encode_address(account)],
+        ),
+    )
+}
+
+/// calldata of `aggregate3((address,bool,bytes)[])`
+pub fn encode_aggregate3(calls: &[Call3]) -> Vec<u8> {
+    let calls = calls
+        .iter()
+        .map(|call| {
+            Token::Tuple(vec![
+                Token::Address(call.target),
+                Token::Bool(call.allow_failure),
+                Token::Bytes(call.call_data.clone()),
+            ])
+        })
+        .collect();
+
+    let mut data = abi::selector("aggregate3((address,bool,bytes)[])").to_vec();
+    data.extend_from_slice(&abi::encode(&[Token::Array(calls)]));
+    data
+}
+
+/// decode the `(bool success, bytes returnData)[]` returned by `aggregate3`. failed calls are
+/// `None`
+pub fn decode_aggregate3(data: &[u8]) -> Result<Vec<Option<Vec<u8>>>, DecodeError> {
+    let result = ParamType::Array(Box::new(ParamType::Tuple(vec![
+        ParamType::Bool,
+        ParamType::Bytes,
+    ])));
+    let results = abi::decode(&[result], data)?
+        .into_iter()
+        .next()
+        .and_then(Token::into_tokens)
+        .unwrap_or_default();
+    results
+        .into_iter()
+        .map(|result| {
+            let mut fields = result.into_tokens().unwrap_or_default().into_iter();
+            match (
+                fields.next().and_then(Token::into_bool),
+                fields.next().and_then(Token::into_bytes),
+            ) {
+                (Some(success), Some(return_data)) => {
+                    Ok(if success { Some(return_data) } else { None })
+                }
+                _ => Err(DecodeError::InvalidValue(String::from(
+                    "malformed aggregate3 result",
+                ))),
+            }
+        })
+        .collect()
+}
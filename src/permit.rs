@@ -0,0 +1,76 @@
+use crate::{abi, Eip712Domain, TypedData};
+use serde_json::{json, Value};
+use web3::types::{H160, H256, U256};
+
+/// The `Permit` message of an ERC-2612 gasless approval
+#[derive(Debug, Clone, PartialEq)]
+pub struct Permit {
+    pub domain: Eip712Domain,
+    pub owner: H160,
+    pub spender: H160,
+    pub value: U256,
+    pub nonce: U256,
+    pub deadline: U256,
+}
+
+impl TypedData for Permit {
+    fn eip712_domain(&self) -> Eip712Domain {
+        self.domain.clone()
+    }
+
+    fn encode_type(&self) -> String {
+        String::from(
+            "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)",
+        )
+    }
+
+    fn encode_data(&self) -> Vec<u8> {
+        [
+            abi::encode_address(&self.owner),
+            abi::encode_address(&self.spender),
+            abi::encode_uint(self.value),
+            abi::encode_uint(self.nonce),
+            abi::encode_uint(self.deadline),
+        ]
+        .concat()
+    }
+
+    fn message(&self) -> Value {
+        json!({
+            "owner": self.owner,
+            "spender": self.spender,
+            "value": self.value.to_string(),
+            "nonce": self.nonce.to_string(),
+            "deadline": self.deadline.to_string(),
+        })
+    }
+}
+
+/// A permit signature split into the `v`, `r` and `s` arguments of `permit(...)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PermitSignature {
+    pub v: u8,
+    pub r: H256,
+    pub s: H256,
+}
+
+impl PermitSignature {
+    /// split a 65 byte `0x`-prefixed hex signature as returned by the wallet
+    pub fn from_hex(signature: &str) -> Option<PermitSignature> {
+        let hex = signature.trim_start_matches("0x");
+        if hex.len() != 130 {
+            return None;
+        }
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        let v = bytes[64];
+        Some(PermitSignature {
+            // some wallets return the recovery id instead of 27/28
+            v: if v < 27 { v + 27 } else { v },
+            r: H256::from_slice(&bytes[..32]),
+            s: H256::from_slice(&bytes[32..64]),
+        })
+    }
+}
@@ -0,0 +1,14 @@
+use crate::{EthereumError, PermitSignature};
+use web3::{signing, types::H160};
+
+/// the account that signed `message` with `personal_sign`, recovered locally from the 65 byte
+/// `0x`-prefixed hex `signature`. needs the `signing` feature, which pulls in secp256k1
+pub fn recover_signer(message: &str, signature: &str) -> Result<H160, EthereumError> {
+    let parsed = PermitSignature::from_hex(signature)
+        .ok_or_else(|| EthereumError::InvalidRequest(format!("invalid signature {}", signature)))?;
+    let mut rs = parsed.r.as_bytes().to_vec();
+    rs.extend_from_slice(parsed.s.as_bytes());
+    let hash = signing::hash_message(message.as_bytes());
+    signing::recover(hash.as_bytes(), &rs, parsed.v as i32 - 27)
+        .map_err(|err| EthereumError::InvalidRequest(format!("can't recover signer: {:?}", err)))
+}
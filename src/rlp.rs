@@ -0,0 +1,101 @@
+//! Minimal [RLP](https://ethereum.org/en/developers/docs/data-structures-and-encoding/rlp/)
+//! encoding, enough to serialize transactions for offline signing
+use web3::types::U256;
+
+/// encode a byte string
+pub fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        return bytes.to_vec();
+    }
+    let mut encoded = encode_length(bytes.len(), 0x80);
+    encoded.extend_from_slice(bytes);
+    encoded
+}
+
+/// encode an integer as its big-endian bytes without leading zeros, zero being the empty string
+pub fn encode_uint(value: U256) -> Vec<u8> {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    let first = bytes
+        .iter()
+        .position(|byte| *byte != 0)
+        .unwrap_or(bytes.len());
+    encode_bytes(&bytes[first..])
+}
+
+/// encode a list of already encoded items
+pub fn encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let length = items.iter().map(Vec::len).sum();
+    let mut encoded = encode_length(length, 0xc0);
+    for item in items {
+        encoded.extend_from_slice(item);
+    }
+    encoded
+}
+
+/// the prefix of a string (`offset` 0x80) or list (`offset` 0xc0) of `length` bytes
+fn encode_length(length: usize, offset: u8) -> Vec<u8> {
+    if length < 56 {
+        return vec![offset + length as u8];
+    }
+    let bytes = (length as u64).to_be_bytes();
+    let first = bytes
+        .iter()
+        .position(|byte| *byte != 0)
+        .unwrap_or(bytes.len());
+    let mut encoded = vec![offset + 55 + (bytes.len() - first) as u8];
+    encoded.extend_from_slice(&bytes[first..]);
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    #[test]
+    fn single_bytes_below_0x80_encode_as_themselves() {
+        assert_eq!(encode_bytes(&[0x00]), hex("00"));
+        assert_eq!(encode_bytes(&[0x7f]), hex("7f"));
+        assert_eq!(encode_bytes(&[0x80]), hex("8180"));
+    }
+
+    #[test]
+    fn empty_values() {
+        assert_eq!(encode_bytes(&[]), hex("80"));
+        assert_eq!(encode_uint(U256::zero()), hex("80"));
+        assert_eq!(encode_list(&[]), hex("c0"));
+    }
+
+    #[test]
+    fn short_strings_and_integers() {
+        assert_eq!(encode_bytes(b"dog"), hex("83646f67"));
+        assert_eq!(encode_uint(U256::from(15)), hex("0f"));
+        assert_eq!(encode_uint(U256::from(1024)), hex("820400"));
+    }
+
+    #[test]
+    fn short_list() {
+        let items = [encode_bytes(b"cat"), encode_bytes(b"dog")];
+        assert_eq!(encode_list(&items), hex("c88363617483646f67"));
+    }
+
+    #[test]
+    fn long_string() {
+        let lorem = b"Lorem ipsum dolor sit amet, consectetur adipisicing elit";
+        assert_eq!(lorem.len(), 56);
+        let mut expected = hex("b838");
+        expected.extend_from_slice(lorem);
+        assert_eq!(encode_bytes(lorem), expected);
+    }
+
+    #[test]
+    fn long_list() {
+        // six 11 byte items make a 66 byte payload, over the 55 byte short list limit
+        let items = vec![encode_bytes(&[0x61; 10]); 6];
+        let encoded = encode_list(&items);
+        assert_eq!(encoded[..2], hex("f842")[..]);
+        assert_eq!(encoded.len(), 2 + 66);
+        assert_eq!(encoded[2..], items.concat()[..]);
+    }
+}
@@ -0,0 +1,314 @@
+use crate::EthereumError;
+use std::fmt::Write;
+use web3::types::H160;
+
+/// A Sign-In with Ethereum (EIP-4361) message. `sign_in` validates it, then sets `address` and
+/// `chain_id` to the connected wallet's, and `issued_at` to the current time if it is unset
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Siwe {
+    scheme: Option<String>,
+    domain: String,
+    address: Option<H160>,
+    statement: Option<String>,
+    uri: String,
+    version: String,
+    chain_id: Option<u64>,
+    nonce: String,
+    issued_at: Option<String>,
+    expiration_time: Option<String>,
+    not_before: Option<String>,
+    request_id: Option<String>,
+    resources: Vec<String>,
+}
+
+impl Siwe {
+    /// `domain` is the host requesting the sign-in, `uri` the resource it's for and `nonce`
+    /// a random string of at least 8 alphanumeric characters issued by the server
+    pub fn new(
+        domain: impl Into<String>,
+        uri: impl Into<String>,
+        nonce: impl Into<String>,
+    ) -> Siwe {
+        Siwe {
+            domain: domain.into(),
+            uri: uri.into(),
+            version: String::from("1"),
+            nonce: nonce.into(),
+            ..Siwe::default()
+        }
+    }
+
+    pub fn scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.scheme = Some(scheme.into());
+        self
+    }
+
+    pub fn address(mut self, address: H160) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// human readable assertion the user signs. must not contain newlines
+    pub fn statement(mut self, statement: impl Into<String>) -> Self {
+        self.statement = Some(statement.into());
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// RFC 3339 timestamp of when the message was generated
+    pub fn issued_at(mut self, issued_at: impl Into<String>) -> Self {
+        self.issued_at = Some(issued_at.into());
+        self
+    }
+
+    /// RFC 3339 timestamp after which the signed message is no longer valid
+    pub fn expiration_time(mut self, expiration_time: impl Into<String>) -> Self {
+        self.expiration_time = Some(expiration_time.into());
+        self
+    }
+
+    /// RFC 3339 timestamp before which the signed message is not yet valid
+    pub fn not_before(mut self, not_before: impl Into<String>) -> Self {
+        self.not_before = Some(not_before.into());
+        self
+    }
+
+    pub fn request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    pub fn resource(mut self, resource: impl Into<String>) -> Self {
+        self.resources.push(resource.into());
+        self
+    }
+
+    /// check the fields the caller supplies against EIP-4361, naming the first bad one
+    pub fn validate(&self) -> Result<(), EthereumError> {
+        let invalid = |reason: &str| Err(EthereumError::InvalidRequest(format!("siwe {}", reason)));
+        if self.domain.trim().is_empty() {
+            return invalid("domain is required");
+        }
+        if self.uri.trim().is_empty() {
+            return invalid("uri is required");
+        }
+        if self.nonce.len() < 8 || !self.nonce.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return invalid("nonce must be at least 8 alphanumeric characters");
+        }
+        if let Some(statement) = &self.statement {
+            if statement.contains('\n') {
+                return invalid("statement must not contain newlines");
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn has_issued_at(&self) -> bool {
+        self.issued_at.is_some()
+    }
+
+    /// the message to sign as laid out by the EIP-4361 ABNF. None until `address`, `chain_id`
+    /// and `issued_at` are all set
+    pub fn message(&self) -> Option<String> {
+        let address = crate::to_checksum(self.address.as_ref()?);
+        let chain_id = self.chain_id?;
+        let issued_at = self.issued_at.as_ref()?;
+
+        let mut message = String::new();
+        if let Some(scheme) = &self.scheme {
+            let _ = write!(message, "{}://", scheme);
+        }
+        let _ = writeln!(
+            message,
+            "{} wants you to sign in with your Ethereum account:",
+            self.domain
+        );
+        let _ = writeln!(message, "{}", address);
+        message.push('\n');
+        if let Some(statement) = &self.statement {
+            let _ = writeln!(message, "{}", statement);
+        }
+        message.push('\n');
+        let _ = writeln!(message, "URI: {}", self.uri);
+        let _ = writeln!(message, "Version: {}", self.version);
+        let _ = writeln!(message, "Chain ID: {}", chain_id);
+        let _ = writeln!(message, "Nonce: {}", self.nonce);
+        let _ = write!(message, "Issued At: {}", issued_at);
+        if let Some(expiration_time) = &self.expiration_time {
+            let _ = write!(message, "\nExpiration Time: {}", expiration_time);
+        }
+        if let Some(not_before) = &self.not_before {
+            let _ = write!(message, "\nNot Before: {}", not_before);
+        }
+        if let Some(request_id) = &self.request_id {
+            let _ = write!(message, "\nRequest ID: {}", request_id);
+        }
+        if !self.resources.is_empty() {
+            message.push_str("\nResources:");
+            for resource in &self.resources {
+                let _ = write!(message, "\n- {}", resource);
+            }
+        }
+        Some(message)
+    }
+}
+
+/// A signed SIWE message, both parts of which the server needs to verify the sign-in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiweSignature {
+    pub message: String,
+    pub signature: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    /// the example message of EIP-4361
+    fn example() -> Siwe {
+        Siwe::new(
+            "service.invalid",
+            "https://service.invalid/login",
+            "32891756",
+        )
+        .address(H160::from_slice(&hex(
+            "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+        )))
+        .chain_id(1)
+        .issued_at("2021-09-30T16:25:24Z")
+    }
+
+    #[test]
+    fn canonical_example() {
+        let siwe = example()
+            .statement("I accept the ServiceOrg Terms of Service: https://service.invalid/tos")
+            .resource("ipfs://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq/")
+            .resource("https://example.com/my-web2-claim.json");
+        let expected = "\
+service.invalid wants you to sign in with your Ethereum account:
+0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2
+
+I accept the ServiceOrg Terms of Service: https://service.invalid/tos
+
+URI: https://service.invalid/login
+Version: 1
+Chain ID: 1
+Nonce: 32891756
+Issued At: 2021-09-30T16:25:24Z
+Resources:
+- ipfs://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq/
+- https://example.com/my-web2-claim.json";
+        assert_eq!(siwe.message().as_deref(), Some(expected));
+    }
+
+    #[test]
+    fn without_statement() {
+        let expected = "\
+service.invalid wants you to sign in with your Ethereum account:
+0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2
+
+
+URI: https://service.invalid/login
+Version: 1
+Chain ID: 1
+Nonce: 32891756
+Issued At: 2021-09-30T16:25:24Z";
+        assert_eq!(example().message().as_deref(), Some(expected));
+    }
+
+    #[test]
+    fn optional_fields() {
+        let siwe = example()
+            .scheme("https")
+            .expiration_time("2021-10-01T16:25:24Z")
+            .not_before("2021-09-30T16:25:24Z")
+            .request_id("req-1");
+        let expected = "\
+https://service.invalid wants you to sign in with your Ethereum account:
+0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2
+
+
+URI: https://service.invalid/login
+Version: 1
+Chain ID: 1
+Nonce: 32891756
+Issued At: 2021-09-30T16:25:24Z
+Expiration Time: 2021-10-01T16:25:24Z
+Not Before: 2021-09-30T16:25:24Z
+Request ID: req-1";
+        assert_eq!(siwe.message().as_deref(), Some(expected));
+    }
+
+    #[test]
+    fn incomplete_message() {
+        let siwe = Siwe::new(
+            "service.invalid",
+            "https://service.invalid/login",
+            "32891756",
+        );
+        let address = H160::repeat_byte(0x11);
+        assert_eq!(siwe.clone().chain_id(1).issued_at("now").message(), None);
+        assert_eq!(
+            siwe.clone().address(address).issued_at("now").message(),
+            None
+        );
+        assert_eq!(siwe.clone().address(address).chain_id(1).message(), None);
+        assert!(siwe
+            .address(address)
+            .chain_id(1)
+            .issued_at("now")
+            .message()
+            .is_some());
+    }
+
+    #[test]
+    fn validation() {
+        assert!(example().validate().is_ok());
+        let invalid = |siwe: Siwe, field: &str| {
+            assert!(matches!(
+                siwe.validate(),
+                Err(EthereumError::InvalidRequest(reason)) if reason.contains(field)
+            ));
+        };
+        let example = example();
+        invalid(
+            Siwe {
+                domain: String::new(),
+                ..example.clone()
+            },
+            "domain",
+        );
+        invalid(
+            Siwe {
+                uri: String::from(" "),
+                ..example.clone()
+            },
+            "uri",
+        );
+        invalid(
+            Siwe {
+                nonce: String::from("3289175"),
+                ..example.clone()
+            },
+            "nonce",
+        );
+        invalid(
+            Siwe {
+                nonce: String::from("3289-1756"),
+                ..example.clone()
+            },
+            "nonce",
+        );
+        invalid(example.statement("first line\nsecond line"), "statement");
+    }
+}
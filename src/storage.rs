@@ -0,0 +1,28 @@
+//! Best-effort access to `window.localStorage`. it is missing outside of browsers and can throw
+//! in privacy modes, in which case reads find nothing and writes are dropped
+use web_sys::Storage;
+
+/// set while a connection should be restored on the next page load
+pub(crate) const CONNECTED_KEY: &str = "yew-ethereum-provider.connected";
+
+fn local_storage() -> Option<Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+pub(crate) fn get(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok().flatten()
+}
+
+pub(crate) fn set(key: &str, value: &str) {
+    if let Some(storage) = local_storage() {
+        if storage.set_item(key, value).is_err() {
+            log::warn!("could not write {} to localStorage", key);
+        }
+    }
+}
+
+pub(crate) fn remove(key: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(key);
+    }
+}
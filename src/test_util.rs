@@ -0,0 +1,16 @@
+//! Helpers shared by the unit tests
+
+/// bytes of a hex string, ignoring a `0x` prefix and whitespace so vectors can be laid out one
+/// word per line
+pub fn hex(data: &str) -> Vec<u8> {
+    let digits: String = data
+        .trim_start_matches("0x")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    assert!(digits.len() % 2 == 0, "odd number of hex digits");
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).expect("invalid hex digit"))
+        .collect()
+}
@@ -0,0 +1,62 @@
+//! Formatting of unix timestamps for display
+
+/// a short relative description of `timestamp` as seen at `now` (both unix seconds), eg.
+/// `"5m ago"`. timestamps in the future, as produced by clock skew, read as `"just now"`
+pub fn format_relative_time(timestamp: u64, now: u64) -> String {
+    let elapsed = now.saturating_sub(timestamp);
+    match elapsed {
+        0..=9 => String::from("just now"),
+        10..=59 => format!("{}s ago", elapsed),
+        60..=3_599 => format!("{}m ago", elapsed / 60),
+        3_600..=86_399 => format!("{}h ago", elapsed / 3_600),
+        _ => format!("{}d ago", elapsed / 86_400),
+    }
+}
+
+/// current unix time in seconds according to the browser
+pub fn now() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn ago(seconds: u64) -> String {
+        format_relative_time(NOW - seconds, NOW)
+    }
+
+    #[test]
+    fn seconds_boundary() {
+        assert_eq!(ago(0), "just now");
+        assert_eq!(ago(9), "just now");
+        assert_eq!(ago(10), "10s ago");
+    }
+
+    #[test]
+    fn minutes_boundary() {
+        assert_eq!(ago(59), "59s ago");
+        assert_eq!(ago(60), "1m ago");
+    }
+
+    #[test]
+    fn hours_boundary() {
+        assert_eq!(ago(3_599), "59m ago");
+        assert_eq!(ago(3_600), "1h ago");
+    }
+
+    #[test]
+    fn days_boundary() {
+        assert_eq!(ago(86_399), "23h ago");
+        assert_eq!(ago(86_400), "1d ago");
+        assert_eq!(ago(10 * 86_400), "10d ago");
+    }
+
+    #[test]
+    fn future_timestamps_read_as_just_now() {
+        assert_eq!(format_relative_time(NOW + 1, NOW), "just now");
+        assert_eq!(format_relative_time(NOW + 86_400, NOW), "just now");
+    }
+}
@@ -0,0 +1,424 @@
+use crate::{rlp, EthereumError};
+use web3::{
+    signing::keccak256,
+    types::{Bytes, H160, H256, U256, U64},
+};
+
+/// Parameters of an `eth_sendTransaction` call.
+///
+/// Fees can be given either legacy style (`gas_price`) or EIP-1559 style (`max_fee_per_gas` /
+/// `max_priority_fee_per_gas`) but not both. Leaving all of them unset lets the wallet decide.
+#[derive(serde::Serialize, Default, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionRequest {
+    /// sender, defaults to the connected account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<H160>,
+    /// recipient, `None` deploys a contract
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<H160>,
+    /// amount of native currency to send, in wei
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+    /// calldata
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Bytes>,
+    /// gas limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas: Option<U256>,
+    /// legacy gas price
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<U256>,
+    /// EIP-1559 fee cap
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_gas: Option<U256>,
+    /// EIP-1559 tip
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_priority_fee_per_gas: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U256>,
+}
+
+impl TransactionRequest {
+    /// checks the request is consistent before it is handed to the wallet
+    pub fn validate(&self) -> Result<(), EthereumError> {
+        let eip1559 = self.max_fee_per_gas.is_some() || self.max_priority_fee_per_gas.is_some();
+        if self.gas_price.is_some() && eip1559 {
+            return Err(EthereumError::InvalidRequest(String::from(
+                "gas_price can't be combined with max_fee_per_gas/max_priority_fee_per_gas",
+            )));
+        }
+        if let (Some(max_fee), Some(priority_fee)) =
+            (self.max_fee_per_gas, self.max_priority_fee_per_gas)
+        {
+            if priority_fee > max_fee {
+                return Err(EthereumError::InvalidRequest(String::from(
+                    "max_priority_fee_per_gas can't exceed max_fee_per_gas",
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How often `UseEthereumHandle::wait_for_receipt_with` polls the provider
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollConfig {
+    /// delay before the second poll
+    pub interval_ms: u32,
+    /// factor the delay grows by after each poll, `1.0` polls at a fixed interval. the delay
+    /// never grows past `MAX_POLL_INTERVAL_MS`
+    pub backoff: f64,
+}
+
+/// upper bound for the delay between polls however large the backoff
+pub const MAX_POLL_INTERVAL_MS: u32 = 30_000;
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        PollConfig {
+            interval_ms: 1_000,
+            backoff: 1.0,
+        }
+    }
+}
+
+impl PollConfig {
+    /// delay after the `attempt`th poll, counting from 1
+    pub fn delay_ms(&self, attempt: u32) -> u32 {
+        let delay = self.interval_ms as f64 * self.backoff.max(1.0).powi(attempt as i32 - 1);
+        delay.min(MAX_POLL_INTERVAL_MS as f64) as u32
+    }
+}
+
+/// What happened to a mined transaction, decoded from the receipt's `status`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxOutcome {
+    /// status `0x1`
+    Success,
+    /// status `0x0`
+    Reverted,
+    /// receipts from before the Byzantium fork have no status, success is unknown
+    PreByzantium,
+}
+
+impl TxOutcome {
+    pub fn from_status(status: Option<U64>) -> TxOutcome {
+        match status {
+            None => TxOutcome::PreByzantium,
+            Some(status) if status.is_zero() => TxOutcome::Reverted,
+            Some(_) => TxOutcome::Success,
+        }
+    }
+}
+
+/// A mined transaction's receipt together with its decoded outcome
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionReceipt {
+    pub outcome: TxOutcome,
+    pub receipt: web3::types::TransactionReceipt,
+}
+
+impl From<web3::types::TransactionReceipt> for TransactionReceipt {
+    fn from(receipt: web3::types::TransactionReceipt) -> Self {
+        TransactionReceipt {
+            outcome: TxOutcome::from_status(receipt.status),
+            receipt,
+        }
+    }
+}
+
+impl TransactionReceipt {
+    pub fn succeeded(&self) -> bool {
+        self.outcome == TxOutcome::Success
+    }
+
+    pub fn transaction_hash(&self) -> web3::types::H256 {
+        self.receipt.transaction_hash
+    }
+
+    pub fn block_number(&self) -> Option<u64> {
+        self.receipt.block_number.map(|number| number.as_u64())
+    }
+}
+
+/// Fees of an `UnsignedTransaction`, which also decide its type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionFees {
+    /// a legacy transaction signed per EIP-155
+    Legacy { gas_price: U256 },
+    /// an EIP-1559 (type 2) transaction
+    Eip1559 {
+        max_fee_per_gas: U256,
+        max_priority_fee_per_gas: U256,
+    },
+}
+
+/// A transaction with every field filled in, to be signed outside of the wallet (eg. with a
+/// hardware wallet) and broadcast with `UseEthereumHandle::send_raw_transaction`. see
+/// `UseEthereumHandle::build_unsigned_tx`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsignedTransaction {
+    pub chain_id: u64,
+    pub nonce: U256,
+    pub gas: U256,
+    pub fees: TransactionFees,
+    /// `None` deploys a contract
+    pub to: Option<H160>,
+    pub value: U256,
+    pub data: Bytes,
+}
+
+impl UnsignedTransaction {
+    /// the bytes whose keccak256 hash is signed
+    pub fn signing_payload(&self) -> Vec<u8> {
+        match self.fees {
+            // EIP-155 replaces the signature with the chain id and two empty values
+            TransactionFees::Legacy { .. } => rlp::encode_list(&self.fields(vec![
+                rlp::encode_uint(U256::from(self.chain_id)),
+                rlp::encode_uint(U256::zero()),
+                rlp::encode_uint(U256::zero()),
+            ])),
+            TransactionFees::Eip1559 { .. } => {
+                self.typed(rlp::encode_list(&self.fields(Vec::new())))
+            }
+        }
+    }
+
+    pub fn signing_hash(&self) -> H256 {
+        H256::from(keccak256(&self.signing_payload()))
+    }
+
+    /// the raw transaction for `eth_sendRawTransaction`, given the signature over
+    /// `signing_hash`. `recovery_id` is the signature's y parity, 0 or 1
+    pub fn encode_signed(&self, recovery_id: u8, r: H256, s: H256) -> Vec<u8> {
+        let signature = |v: U256| {
+            vec![
+                rlp::encode_uint(v),
+                rlp::encode_uint(U256::from_big_endian(r.as_bytes())),
+                rlp::encode_uint(U256::from_big_endian(s.as_bytes())),
+            ]
+        };
+        match self.fees {
+            TransactionFees::Legacy { .. } => {
+                let v = U256::from(self.chain_id) * 2 + 35 + recovery_id;
+                rlp::encode_list(&self.fields(signature(v)))
+            }
+            TransactionFees::Eip1559 { .. } => {
+                let fields = self.fields(signature(U256::from(recovery_id)));
+                self.typed(rlp::encode_list(&fields))
+            }
+        }
+    }
+
+    /// the encoded fields of the transaction type followed by `trailer`
+    fn fields(&self, trailer: Vec<Vec<u8>>) -> Vec<Vec<u8>> {
+        let to = match &self.to {
+            Some(to) => rlp::encode_bytes(to.as_bytes()),
+            None => rlp::encode_bytes(&[]),
+        };
+        let mut fields = match self.fees {
+            TransactionFees::Legacy { gas_price } => vec![
+                rlp::encode_uint(self.nonce),
+                rlp::encode_uint(gas_price),
+                rlp::encode_uint(self.gas),
+                to,
+                rlp::encode_uint(self.value),
+                rlp::encode_bytes(&self.data.0),
+            ],
+            TransactionFees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => vec![
+                rlp::encode_uint(U256::from(self.chain_id)),
+                rlp::encode_uint(self.nonce),
+                rlp::encode_uint(max_priority_fee_per_gas),
+                rlp::encode_uint(max_fee_per_gas),
+                rlp::encode_uint(self.gas),
+                to,
+                rlp::encode_uint(self.value),
+                rlp::encode_bytes(&self.data.0),
+                // empty access list
+                rlp::encode_list(&[]),
+            ],
+        };
+        fields.extend(trailer);
+        fields
+    }
+
+    /// prefix an EIP-2718 typed transaction payload with its type
+    fn typed(&self, payload: Vec<u8>) -> Vec<u8> {
+        let mut typed = vec![0x02];
+        typed.extend(payload);
+        typed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    /// the example transaction of EIP-155
+    fn legacy() -> UnsignedTransaction {
+        UnsignedTransaction {
+            chain_id: 1,
+            nonce: U256::from(9),
+            gas: U256::from(21_000),
+            fees: TransactionFees::Legacy {
+                gas_price: U256::from(20_000_000_000u64),
+            },
+            to: Some(H160::repeat_byte(0x35)),
+            value: U256::exp10(18),
+            data: Bytes::default(),
+        }
+    }
+
+    /// an ERC20 `transfer(0x1111..., 1000)`
+    fn eip1559() -> UnsignedTransaction {
+        let mut data = hex("a9059cbb");
+        data.extend_from_slice(&crate::abi::encode_address(&H160::repeat_byte(0x11)));
+        data.extend_from_slice(&crate::abi::encode_uint(U256::from(1000)));
+        UnsignedTransaction {
+            chain_id: 5,
+            nonce: U256::from(3),
+            gas: U256::from(60_000),
+            fees: TransactionFees::Eip1559 {
+                max_fee_per_gas: U256::from(30_000_000_000u64),
+                max_priority_fee_per_gas: U256::from(2_000_000_000u64),
+            },
+            to: Some(H160::from_slice(&hex(
+                "dac17f958d2ee523a2206206994597c13d831ec7",
+            ))),
+            value: U256::zero(),
+            data: data.into(),
+        }
+    }
+
+    #[test]
+    fn legacy_signing_payload() {
+        let tx = legacy();
+        assert_eq!(
+            tx.signing_payload(),
+            hex(
+                "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7
+                 64000080018080"
+            )
+        );
+        assert_eq!(
+            tx.signing_hash(),
+            H256::from_slice(&hex(
+                "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"
+            ))
+        );
+    }
+
+    #[test]
+    fn legacy_signed_encoding() {
+        let r = H256::from_slice(&hex(
+            "28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276",
+        ));
+        let s = H256::from_slice(&hex(
+            "67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+        ));
+        // v = 37 = chain_id * 2 + 35 + 0
+        assert_eq!(
+            legacy().encode_signed(0, r, s),
+            hex(
+                "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3
+                 a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa6362
+                 76a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+            )
+        );
+    }
+
+    #[test]
+    fn eip1559_signing_payload() {
+        let tx = eip1559();
+        assert_eq!(
+            tx.signing_payload(),
+            hex(
+                "02f86d050384773594008506fc23ac0082ea6094dac17f958d2ee523a2206206994597c13d83
+                 1ec780b844a9059cbb0000000000000000000000001111111111111111111111111111111111
+                 11111100000000000000000000000000000000000000000000000000000000000003e8c0"
+            )
+        );
+        assert_eq!(
+            tx.signing_hash(),
+            H256::from_slice(&hex(
+                "49edfaaf308ecc3e385e63c69887107659a9a430f0faa9bb9cb141ef9c1c2128"
+            ))
+        );
+    }
+
+    #[test]
+    fn eip1559_signed_encoding() {
+        let (r, s) = (H256::repeat_byte(0x11), H256::repeat_byte(0x22));
+        assert_eq!(
+            eip1559().encode_signed(1, r, s),
+            hex(
+                "02f8b0050384773594008506fc23ac0082ea6094dac17f958d2ee523a2206206994597c13d83
+                 1ec780b844a9059cbb0000000000000000000000001111111111111111111111111111111111
+                 11111100000000000000000000000000000000000000000000000000000000000003e8c001a0
+                 1111111111111111111111111111111111111111111111111111111111111111a02222222222
+                 222222222222222222222222222222222222222222222222222222"
+            )
+        );
+    }
+
+    /// a receipt as `eth_getTransactionReceipt` returns it, with `status` if given
+    fn receipt(status: Option<&str>) -> TransactionReceipt {
+        let mut receipt = serde_json::json!({
+            "transactionHash": format!("0x{}", "ab".repeat(32)),
+            "transactionIndex": "0x0",
+            "blockHash": format!("0x{}", "cd".repeat(32)),
+            "blockNumber": "0x10",
+            "from": format!("0x{}", "11".repeat(20)),
+            "to": format!("0x{}", "22".repeat(20)),
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "contractAddress": null,
+            "logs": [],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+        });
+        if let Some(status) = status {
+            receipt["status"] = status.into();
+        }
+        TransactionReceipt::from(
+            serde_json::from_value::<web3::types::TransactionReceipt>(receipt).unwrap(),
+        )
+    }
+
+    #[test]
+    fn outcome_from_status() {
+        assert_eq!(TxOutcome::from_status(Some(U64::one())), TxOutcome::Success);
+        assert_eq!(
+            TxOutcome::from_status(Some(U64::zero())),
+            TxOutcome::Reverted
+        );
+        assert_eq!(TxOutcome::from_status(None), TxOutcome::PreByzantium);
+    }
+
+    #[test]
+    fn successful_receipt() {
+        let receipt = receipt(Some("0x1"));
+        assert_eq!(receipt.outcome, TxOutcome::Success);
+        assert!(receipt.succeeded());
+        assert_eq!(receipt.block_number(), Some(16));
+        assert_eq!(receipt.transaction_hash(), H256::repeat_byte(0xab));
+    }
+
+    #[test]
+    fn reverted_receipt() {
+        let receipt = receipt(Some("0x0"));
+        assert_eq!(receipt.outcome, TxOutcome::Reverted);
+        assert!(!receipt.succeeded());
+    }
+
+    #[test]
+    fn receipt_without_status() {
+        let receipt = receipt(None);
+        assert_eq!(receipt.outcome, TxOutcome::PreByzantium);
+        assert!(!receipt.succeeded());
+    }
+}
@@ -0,0 +1,323 @@
+use serde_json::{json, Map, Value};
+use web3::{
+    signing::keccak256,
+    types::{H160, H256, U256},
+};
+
+/// The `EIP712Domain` of a typed-data signature. `None` fields are left out of the domain type.
+#[derive(serde::Serialize, Default, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip712Domain {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// serialized as a json number, wallets compare it against the active chain
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_chain_id"
+    )]
+    pub chain_id: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verifying_contract: Option<H160>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub salt: Option<H256>,
+}
+
+fn serialize_chain_id<S: serde::Serializer>(
+    chain_id: &Option<U256>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match chain_id {
+        Some(chain_id) => serializer.serialize_u64(chain_id.low_u64()),
+        None => serializer.serialize_none(),
+    }
+}
+
+impl Eip712Domain {
+    /// `(type, name)` of each field that is set, in the order fixed by EIP-712
+    fn fields(&self) -> Vec<(&'static str, &'static str)> {
+        let mut fields = Vec::new();
+        if self.name.is_some() {
+            fields.push(("string", "name"));
+        }
+        if self.version.is_some() {
+            fields.push(("string", "version"));
+        }
+        if self.chain_id.is_some() {
+            fields.push(("uint256", "chainId"));
+        }
+        if self.verifying_contract.is_some() {
+            fields.push(("address", "verifyingContract"));
+        }
+        if self.salt.is_some() {
+            fields.push(("bytes32", "salt"));
+        }
+        fields
+    }
+
+    pub fn encode_type(&self) -> String {
+        let fields: Vec<String> = self
+            .fields()
+            .iter()
+            .map(|(kind, name)| format!("{} {}", kind, name))
+            .collect();
+        format!("EIP712Domain({})", fields.join(","))
+    }
+
+    /// `hashStruct(domain)`, the domain separator
+    pub fn separator(&self) -> H256 {
+        let mut encoded = keccak256(self.encode_type().as_bytes()).to_vec();
+        if let Some(name) = &self.name {
+            encoded.extend_from_slice(&keccak256(name.as_bytes()));
+        }
+        if let Some(version) = &self.version {
+            encoded.extend_from_slice(&keccak256(version.as_bytes()));
+        }
+        if let Some(chain_id) = self.chain_id {
+            encoded.extend_from_slice(&crate::abi::encode_uint(chain_id));
+        }
+        if let Some(verifying_contract) = &self.verifying_contract {
+            encoded.extend_from_slice(&crate::abi::encode_address(verifying_contract));
+        }
+        if let Some(salt) = &self.salt {
+            encoded.extend_from_slice(salt.as_bytes());
+        }
+        H256::from(keccak256(&encoded))
+    }
+}
+
+/// A struct that can be signed with EIP-712 typed-data signing.
+///
+/// Implementors describe their type with `encode_type` (the primary type followed by any
+/// referenced struct types, as defined by EIP-712), their field values with `encode_data` for
+/// hashing and `message` for the wallet, and the signing domain with `eip712_domain`. See
+/// `UseEthereumHandle::sign_typed_data_struct`.
+pub trait TypedData {
+    fn eip712_domain(&self) -> Eip712Domain;
+
+    /// eg. `Mail(Person from,Person to,string contents)Person(string name,address wallet)`
+    fn encode_type(&self) -> String;
+
+    /// the concatenated 32-byte encodings of the struct's field values
+    fn encode_data(&self) -> Vec<u8>;
+
+    /// the field values as the json object passed to the wallet
+    fn message(&self) -> Value;
+
+    fn type_hash(&self) -> H256 {
+        H256::from(keccak256(self.encode_type().as_bytes()))
+    }
+
+    fn hash_struct(&self) -> H256 {
+        let mut encoded = self.type_hash().as_bytes().to_vec();
+        encoded.extend_from_slice(&self.encode_data());
+        H256::from(keccak256(&encoded))
+    }
+
+    /// the digest the wallet signs: `keccak256(0x1901 ‖ domainSeparator ‖ hashStruct(message))`
+    fn signing_hash(&self) -> H256 {
+        let mut encoded = vec![0x19, 0x01];
+        encoded.extend_from_slice(self.eip712_domain().separator().as_bytes());
+        encoded.extend_from_slice(self.hash_struct().as_bytes());
+        H256::from(keccak256(&encoded))
+    }
+
+    /// the full `eth_signTypedData_v4` payload with `types` derived from `encode_type`
+    fn to_json(&self) -> Value {
+        let domain = self.eip712_domain();
+        let structs = parse_encoded_type(&self.encode_type());
+        let primary_type = structs
+            .first()
+            .map(|(name, _)| name.clone())
+            .unwrap_or_default();
+
+        let mut types = Map::new();
+        types.insert(String::from("EIP712Domain"), fields_json(&domain.fields()));
+        for (name, fields) in structs.iter() {
+            let fields: Vec<(&str, &str)> = fields
+                .iter()
+                .map(|(kind, name)| (kind.as_str(), name.as_str()))
+                .collect();
+            types.insert(name.clone(), fields_json(&fields));
+        }
+
+        json!({
+            "types": types,
+            "primaryType": primary_type,
+            "domain": domain,
+            "message": self.message(),
+        })
+    }
+}
+
+fn fields_json(fields: &[(&str, &str)]) -> Value {
+    Value::Array(
+        fields
+            .iter()
+            .map(|(kind, name)| json!({ "name": name, "type": kind }))
+            .collect(),
+    )
+}
+
+/// split `A(uint256 x,B b)B(string s)` into `[("A", [("uint256", "x"), ("B", "b")]), ...]`
+fn parse_encoded_type(encoded: &str) -> Vec<(String, Vec<(String, String)>)> {
+    encoded
+        .split(')')
+        .filter_map(|definition| {
+            let (name, fields) = definition.split_once('(')?;
+            let fields = fields
+                .split(',')
+                .filter_map(|field| {
+                    let (kind, name) = field.trim().rsplit_once(' ')?;
+                    Some((kind.trim().to_string(), name.to_string()))
+                })
+                .collect();
+            Some((name.trim().to_string(), fields))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{abi, test_util::hex};
+
+    fn address(address: &str) -> H160 {
+        H160::from_slice(&hex(address))
+    }
+
+    struct Person {
+        name: &'static str,
+        wallet: H160,
+    }
+
+    impl Person {
+        fn hash_struct(&self) -> [u8; 32] {
+            let mut encoded = keccak256(b"Person(string name,address wallet)").to_vec();
+            encoded.extend_from_slice(&keccak256(self.name.as_bytes()));
+            encoded.extend_from_slice(&abi::encode_address(&self.wallet));
+            keccak256(&encoded)
+        }
+    }
+
+    /// the example of EIP-712
+    struct Mail {
+        from: Person,
+        to: Person,
+        contents: &'static str,
+    }
+
+    impl TypedData for Mail {
+        fn eip712_domain(&self) -> Eip712Domain {
+            Eip712Domain {
+                name: Some(String::from("Ether Mail")),
+                version: Some(String::from("1")),
+                chain_id: Some(U256::one()),
+                verifying_contract: Some(address("0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC")),
+                salt: None,
+            }
+        }
+
+        fn encode_type(&self) -> String {
+            String::from(
+                "Mail(Person from,Person to,string contents)Person(string name,address wallet)",
+            )
+        }
+
+        fn encode_data(&self) -> Vec<u8> {
+            let mut encoded = self.from.hash_struct().to_vec();
+            encoded.extend_from_slice(&self.to.hash_struct());
+            encoded.extend_from_slice(&keccak256(self.contents.as_bytes()));
+            encoded
+        }
+
+        fn message(&self) -> Value {
+            json!({
+                "from": { "name": self.from.name, "wallet": self.from.wallet },
+                "to": { "name": self.to.name, "wallet": self.to.wallet },
+                "contents": self.contents,
+            })
+        }
+    }
+
+    fn mail() -> Mail {
+        Mail {
+            from: Person {
+                name: "Cow",
+                wallet: address("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"),
+            },
+            to: Person {
+                name: "Bob",
+                wallet: address("0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"),
+            },
+            contents: "Hello, Bob!",
+        }
+    }
+
+    #[test]
+    fn domain_separator() {
+        let domain = mail().eip712_domain();
+        assert_eq!(
+            domain.encode_type(),
+            "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+        );
+        assert_eq!(
+            domain.separator().as_bytes(),
+            &hex("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f")[..]
+        );
+    }
+
+    #[test]
+    fn mail_hashes() {
+        let mail = mail();
+        assert_eq!(
+            mail.type_hash().as_bytes(),
+            &hex("a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2")[..]
+        );
+        assert_eq!(
+            mail.hash_struct().as_bytes(),
+            &hex("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e")[..]
+        );
+        assert_eq!(
+            mail.signing_hash().as_bytes(),
+            &hex("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2")[..]
+        );
+    }
+
+    #[test]
+    fn mail_json() {
+        let json = mail().to_json();
+        assert_eq!(json["primaryType"], "Mail");
+        assert_eq!(
+            json["types"],
+            json!({
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" },
+                ],
+                "Mail": [
+                    { "name": "from", "type": "Person" },
+                    { "name": "to", "type": "Person" },
+                    { "name": "contents", "type": "string" },
+                ],
+                "Person": [
+                    { "name": "name", "type": "string" },
+                    { "name": "wallet", "type": "address" },
+                ],
+            })
+        );
+        assert_eq!(
+            json["domain"],
+            json!({
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xcccccccccccccccccccccccccccccccccccccccc",
+            })
+        );
+        assert_eq!(json["message"]["contents"], "Hello, Bob!");
+    }
+}
@@ -0,0 +1,68 @@
+//! Conversions between raw integer token amounts and human readable decimal strings
+use web3::types::U256;
+
+/// format a raw integer amount (eg. wei) as a decimal string with `decimals` fractional digits.
+/// trailing zeros are trimmed, so `format_units(1_500_000_000_000_000_000, 18)` is `"1.5"`
+pub fn format_units(value: U256, decimals: u32) -> String {
+    if decimals == 0 {
+        return value.to_string();
+    }
+
+    let digits = value.to_string();
+    let decimals = decimals as usize;
+    let (whole, fraction) = if digits.len() > decimals {
+        let (whole, fraction) = digits.split_at(digits.len() - decimals);
+        (whole.to_string(), fraction.to_string())
+    } else {
+        (
+            String::from("0"),
+            format!("{:0>width$}", digits, width = decimals),
+        )
+    };
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// like `format_units` but keeps at most `precision` fractional digits (truncating, not rounding)
+pub fn format_units_truncated(value: U256, decimals: u32, precision: usize) -> String {
+    let formatted = format_units(value, decimals);
+    match formatted.split_once('.') {
+        Some((whole, fraction)) => {
+            let fraction = fraction[..fraction.len().min(precision)].trim_end_matches('0');
+            if fraction.is_empty() {
+                whole.to_string()
+            } else {
+                format!("{}.{}", whole, fraction)
+            }
+        }
+        None => formatted,
+    }
+}
+
+/// parse a decimal string such as `"1.5"` into a raw integer amount with `decimals` fractional
+/// digits. `None` if it isn't a plain non-negative decimal, has more fractional digits than
+/// `decimals` or overflows
+pub fn parse_units(value: &str, decimals: u32) -> Option<U256> {
+    let value = value.trim();
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if (whole.is_empty() && fraction.is_empty())
+        || fraction.len() > decimals as usize
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let digits = format!("{}{:0<width$}", whole, fraction, width = decimals as usize);
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        return Some(U256::zero());
+    }
+    U256::from_dec_str(digits).ok()
+}
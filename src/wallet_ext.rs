@@ -0,0 +1,35 @@
+//! Wrappers for non-standard wallet rpc methods.
+//!
+//! These are only implemented by some wallets (mostly mobile ones), so every function here can
+//! fail with `EthereumError::Unsupported` and callers should offer a fallback.
+use crate::{EthereumError, UseEthereumHandle};
+use serde_json::{json, Value};
+
+/// `wallet_scanQRCode`: open the wallet's qr code scanner and return the scanned text. with a
+/// `regex` the wallet only accepts codes matching it, eg. `"^0x[a-fA-F0-9]{40}$"` for addresses
+pub async fn scan_qr_code(
+    ethereum: &UseEthereumHandle,
+    regex: Option<String>,
+) -> Result<String, EthereumError> {
+    let params = match regex {
+        Some(regex) => vec![json!(regex)],
+        None => vec![],
+    };
+    let scanned = ethereum.request("wallet_scanQRCode", params).await?;
+    Ok(web3::helpers::decode(scanned)?)
+}
+
+/// `wallet_getPermissions` (EIP-2255): the permissions the dapp currently holds, as returned by
+/// the wallet
+pub async fn get_permissions(ethereum: &UseEthereumHandle) -> Result<Vec<Value>, EthereumError> {
+    let permissions = ethereum.request("wallet_getPermissions", vec![]).await?;
+    Ok(web3::helpers::decode(permissions)?)
+}
+
+/// `wallet_registerOnboarding`: tell MetaMask onboarding to return to this page after install
+pub async fn register_onboarding(ethereum: &UseEthereumHandle) -> Result<bool, EthereumError> {
+    let registered = ethereum
+        .request("wallet_registerOnboarding", vec![])
+        .await?;
+    Ok(web3::helpers::decode(registered)?)
+}
@@ -0,0 +1,111 @@
+use js_sys::{Object, Reflect};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::Event;
+
+/// event wallets answer by dispatching `ANNOUNCE_PROVIDER`, see EIP-6963
+const REQUEST_PROVIDER: &str = "eip6963:requestProvider";
+const ANNOUNCE_PROVIDER: &str = "eip6963:announceProvider";
+
+/// Identity of an injected wallet, for showing its name and logo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletInfo {
+    /// display name, eg. "MetaMask"
+    pub name: String,
+    /// reverse dns identifier as used by EIP-6963, eg. "io.metamask"
+    pub rdns: Option<String>,
+    /// data uri of the wallet's logo, when the wallet announces one through EIP-6963
+    pub icon: Option<String>,
+}
+
+/// identity flags set on `window.ethereum`, most specific first since many wallets also set
+/// `isMetaMask` for compatibility
+const WALLET_FLAGS: [(&str, &str, &str); 8] = [
+    ("isBraveWallet", "Brave Wallet", "com.brave.wallet"),
+    ("isRabby", "Rabby", "io.rabby"),
+    ("isCoinbaseWallet", "Coinbase Wallet", "com.coinbase.wallet"),
+    ("isTrust", "Trust Wallet", "com.trustwallet.app"),
+    ("isOkxWallet", "OKX Wallet", "com.okex.wallet"),
+    ("isPhantom", "Phantom", "app.phantom"),
+    ("isFrame", "Frame", "sh.frame"),
+    ("isMetaMask", "MetaMask", "io.metamask"),
+];
+
+impl WalletInfo {
+    /// identify an EIP-1193 provider by the EIP-6963 info it announces, falling back to its
+    /// `is*` flags for wallets that don't announce themselves
+    pub(crate) fn detect(provider: &JsValue) -> WalletInfo {
+        WalletInfo::announced(provider).unwrap_or_else(|| WalletInfo::from_flags(provider))
+    }
+
+    /// the info `provider` announces in reply to `eip6963:requestProvider`. wallets announce
+    /// while the request is being dispatched, so it is known once `dispatch_event` returns
+    fn announced(provider: &JsValue) -> Option<WalletInfo> {
+        let window = web_sys::window()?;
+        let found = Rc::new(RefCell::new(None));
+        let listener = {
+            let found = found.clone();
+            let provider = provider.clone();
+            Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+                let detail = get(&event, "detail");
+                // every wallet announces, only the one behind `provider` is of interest
+                if Object::is(&get(&detail, "provider"), &provider) {
+                    *found.borrow_mut() = WalletInfo::from_announcement(&get(&detail, "info"));
+                }
+            })
+        };
+        let callback = listener.as_ref().unchecked_ref();
+        window
+            .add_event_listener_with_callback(ANNOUNCE_PROVIDER, callback)
+            .ok()?;
+        let dispatched =
+            Event::new(REQUEST_PROVIDER).and_then(|request| window.dispatch_event(&request));
+        let _ = window.remove_event_listener_with_callback(ANNOUNCE_PROVIDER, callback);
+        if let Err(err) = dispatched {
+            log::warn!(
+                "failed to request EIP-6963 announcements: {}",
+                crate::js_error_to_string(&err)
+            );
+        }
+        found.take()
+    }
+
+    /// the `EIP6963ProviderInfo` of an announcement, `None` without a name
+    fn from_announcement(info: &JsValue) -> Option<WalletInfo> {
+        let field = |name: &str| {
+            get(info, name)
+                .as_string()
+                .filter(|value| !value.is_empty())
+        };
+        Some(WalletInfo {
+            name: field("name")?,
+            rdns: field("rdns"),
+            icon: field("icon"),
+        })
+    }
+
+    /// identify an EIP-1193 provider by its `is*` flags
+    fn from_flags(provider: &JsValue) -> WalletInfo {
+        let flag = |name: &str| get(provider, name).as_bool().unwrap_or(false);
+        match WALLET_FLAGS
+            .iter()
+            .find(|(flag_name, _, _)| flag(flag_name))
+        {
+            Some((_, name, rdns)) => WalletInfo {
+                name: name.to_string(),
+                rdns: Some(rdns.to_string()),
+                icon: None,
+            },
+            None => WalletInfo {
+                name: String::from("Injected wallet"),
+                rdns: None,
+                icon: None,
+            },
+        }
+    }
+}
+
+/// `target[name]`, undefined when it can't be read
+fn get(target: &JsValue, name: &str) -> JsValue {
+    Reflect::get(target, &JsValue::from_str(name)).unwrap_or(JsValue::UNDEFINED)
+}
@@ -0,0 +1,98 @@
+#![cfg(target_arch = "wasm32")]
+
+mod common;
+
+use common::{root, settle, Latest};
+use std::{cell::Cell, rc::Rc, time::Duration};
+use wasm_bindgen_test::*;
+use yew::{platform::time::sleep, prelude::*};
+use yew_ethereum_provider::{use_async_action, UseAsyncActionHandle};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Properties, PartialEq)]
+struct Props {
+    /// whether the action fails
+    fail: bool,
+    runs: Rc<Cell<u32>>,
+    latest: Latest<UseAsyncActionHandle<u32, String>>,
+}
+
+/// an action taking 50ms to answer 42, or to fail
+#[function_component]
+fn Harness(props: &Props) -> Html {
+    let fail = props.fail;
+    let runs = props.runs.clone();
+    props.latest.set(use_async_action(move || {
+        runs.set(runs.get() + 1);
+        async move {
+            sleep(Duration::from_millis(50)).await;
+            if fail {
+                Err(String::from("boom"))
+            } else {
+                Ok(42)
+            }
+        }
+    }));
+    html! {}
+}
+
+async fn render(fail: bool) -> (Latest<UseAsyncActionHandle<u32, String>>, Rc<Cell<u32>>) {
+    let latest = Latest::default();
+    let runs = Rc::new(Cell::new(0));
+    yew::Renderer::<Harness>::with_root_and_props(
+        root(),
+        Props {
+            fail,
+            runs: runs.clone(),
+            latest: latest.clone(),
+        },
+    )
+    .render();
+    settle().await;
+    (latest, runs)
+}
+
+#[wasm_bindgen_test]
+async fn successful_run() {
+    let (latest, runs) = render(false).await;
+    assert!(!latest.get().pending);
+
+    latest.get().run.emit(());
+    settle().await;
+    assert!(latest.get().pending);
+    sleep(Duration::from_millis(100)).await;
+
+    let action = latest.get();
+    assert!(!action.pending);
+    assert_eq!(action.result, Some(42));
+    assert_eq!(action.error, None);
+    assert_eq!(runs.get(), 1);
+}
+
+#[wasm_bindgen_test]
+async fn failed_run() {
+    let (latest, _) = render(true).await;
+
+    latest.get().run.emit(());
+    sleep(Duration::from_millis(100)).await;
+
+    let action = latest.get();
+    assert!(!action.pending);
+    assert_eq!(action.result, None);
+    assert_eq!(action.error.as_deref(), Some("boom"));
+}
+
+#[wasm_bindgen_test]
+async fn runs_while_pending_are_ignored() {
+    let (latest, runs) = render(false).await;
+
+    latest.get().run.emit(());
+    latest.get().run.emit(());
+    settle().await;
+    latest.get().run.emit(());
+    sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(runs.get(), 1);
+    assert_eq!(latest.get().result, Some(42));
+}
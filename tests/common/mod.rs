@@ -0,0 +1,249 @@
+#![allow(dead_code)]
+
+use js_sys::{Array, Function, Reflect};
+use std::{cell::RefCell, rc::Rc, time::Duration};
+use wasm_bindgen::{JsCast, JsValue};
+use web3::{transports::eip_1193::Provider, types::H160};
+use yew::{platform::time::sleep, prelude::*, AppHandle};
+use yew_ethereum_provider::{use_block_number, use_ethereum, UseEthereumHandle};
+#[cfg(feature = "mock")]
+use yew_ethereum_provider::{use_mock_ethereum, MockProvider};
+
+/// what a harness returned on its latest render, the `UseEthereumHandle` unless specified
+pub struct Latest<T = UseEthereumHandle>(Rc<RefCell<Option<T>>>);
+
+impl<T: Clone> Latest<T> {
+    pub fn get(&self) -> T {
+        self.0.borrow().clone().expect("harness not rendered")
+    }
+
+    pub fn set(&self, value: T) {
+        *self.0.borrow_mut() = Some(value);
+    }
+}
+
+impl<T> Clone for Latest<T> {
+    fn clone(&self) -> Self {
+        Latest(self.0.clone())
+    }
+}
+
+impl<T> Default for Latest<T> {
+    fn default() -> Self {
+        Latest(Rc::default())
+    }
+}
+
+impl<T> PartialEq for Latest<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(feature = "mock")]
+#[derive(Properties, PartialEq)]
+pub struct HarnessProps {
+    pub mock: MockProvider,
+    pub latest: Latest,
+}
+
+#[cfg(feature = "mock")]
+#[function_component]
+pub fn Harness(props: &HarnessProps) -> Html {
+    let ethereum = use_mock_ethereum(props.mock.clone());
+    props.latest.set(ethereum);
+    html! {}
+}
+
+/// mount a `Harness` on `mock`. the app stays mounted until the handle is destroyed
+#[cfg(feature = "mock")]
+pub async fn render(mock: MockProvider) -> (Latest, AppHandle<Harness>) {
+    let latest = Latest::default();
+    let app = yew::Renderer::<Harness>::with_root_and_props(
+        root(),
+        HarnessProps {
+            mock,
+            latest: latest.clone(),
+        },
+    )
+    .render();
+    settle().await;
+    (latest, app)
+}
+
+/// An EIP-1193 provider in plain javascript, connected with `accounts` on `chain_id`. it counts
+/// the requests it gets and emits events on demand
+#[derive(Clone, PartialEq)]
+pub struct FakeWallet(JsValue);
+
+const FAKE_WALLET: &str = r#"
+const listeners = {};
+const counts = {};
+const overrides = {};
+let subscriptions = 0;
+return {
+    request({ method }) {
+        counts[method] = (counts[method] || 0) + 1;
+        if (method in overrides) {
+            const { result, error } = overrides[method];
+            return error ? Promise.reject(error) : Promise.resolve(result);
+        }
+        switch (method) {
+            case "eth_accounts":
+            case "eth_requestAccounts":
+                return Promise.resolve(accounts);
+            case "eth_chainId":
+                return Promise.resolve(chainId);
+            case "eth_blockNumber":
+                return Promise.resolve("0x10");
+            case "eth_subscribe":
+                subscriptions += 1;
+                return Promise.resolve("0x" + subscriptions.toString(16));
+            case "eth_unsubscribe":
+                return Promise.resolve(true);
+            default:
+                return Promise.reject({ code: 4200, message: "unsupported method " + method });
+        }
+    },
+    on(event, listener) {
+        (listeners[event] = listeners[event] || []).push(listener);
+    },
+    removeListener(event, listener) {
+        listeners[event] = (listeners[event] || []).filter((other) => other !== listener);
+    },
+    respond(method, result) {
+        overrides[method] = { result };
+    },
+    reject(method, code, message) {
+        overrides[method] = { error: { code, message } };
+    },
+    emit(event, value) {
+        (listeners[event] || []).slice().forEach((listener) => listener(value));
+    },
+    count(method) {
+        return counts[method] || 0;
+    },
+    listenerCount(event) {
+        return (listeners[event] || []).length;
+    },
+};
+"#;
+
+impl FakeWallet {
+    pub fn new(accounts: &[H160], chain_id: u64) -> FakeWallet {
+        let wallet = Function::new_with_args("accounts, chainId", FAKE_WALLET)
+            .call2(
+                &JsValue::NULL,
+                &addresses(accounts),
+                &JsValue::from_str(&format!("0x{:x}", chain_id)),
+            )
+            .unwrap();
+        FakeWallet(wallet)
+    }
+
+    pub fn provider(&self) -> Provider {
+        self.0.clone().unchecked_into()
+    }
+
+    /// how many `method` requests the wallet got
+    pub fn requests(&self, method: &str) -> u32 {
+        self.call("count", &JsValue::from_str(method))
+            .as_f64()
+            .unwrap() as u32
+    }
+
+    /// how many listeners of `event` are registered
+    pub fn listeners(&self, event: &str) -> u32 {
+        self.call("listenerCount", &JsValue::from_str(event))
+            .as_f64()
+            .unwrap() as u32
+    }
+
+    /// answer every `method` request with `result`
+    pub fn respond(&self, method: &str, result: &JsValue) {
+        self.function("respond")
+            .call2(&self.0, &JsValue::from_str(method), result)
+            .unwrap();
+    }
+
+    /// fail every `method` request with a json-rpc error
+    pub fn reject(&self, method: &str, code: i64, message: &str) {
+        self.function("reject")
+            .call3(
+                &self.0,
+                &JsValue::from_str(method),
+                &JsValue::from_f64(code as f64),
+                &JsValue::from_str(message),
+            )
+            .unwrap();
+    }
+
+    /// call every listener of `event` with `value`
+    pub fn emit(&self, event: &str, value: &JsValue) {
+        self.function("emit")
+            .call2(&self.0, &JsValue::from_str(event), value)
+            .unwrap();
+    }
+
+    fn call(&self, method: &str, arg: &JsValue) -> JsValue {
+        self.function(method).call1(&self.0, arg).unwrap()
+    }
+
+    fn function(&self, name: &str) -> Function {
+        Reflect::get(&self.0, &JsValue::from_str(name))
+            .unwrap()
+            .unchecked_into()
+    }
+}
+
+/// `accounts` as the array of `0x`-prefixed addresses wallets use
+pub fn addresses(accounts: &[H160]) -> JsValue {
+    accounts
+        .iter()
+        .map(|account| JsValue::from_str(&format!("{:?}", account)))
+        .collect::<Array>()
+        .into()
+}
+
+#[derive(Properties, PartialEq)]
+pub struct WalletHarnessProps {
+    pub wallet: FakeWallet,
+    pub latest: Latest,
+}
+
+/// `use_ethereum` on a `FakeWallet`, following the block number like a typical app would
+#[function_component]
+pub fn WalletHarness(props: &WalletHarnessProps) -> Html {
+    let ethereum = use_ethereum(Some(props.wallet.provider()));
+    use_block_number(ethereum.clone());
+    *props.latest.0.borrow_mut() = ethereum;
+    html! {}
+}
+
+/// mount a `WalletHarness` on `wallet`. the app stays mounted until the handle is destroyed
+pub async fn render_wallet(wallet: FakeWallet) -> (Latest, AppHandle<WalletHarness>) {
+    let latest = Latest::default();
+    let app = yew::Renderer::<WalletHarness>::with_root_and_props(
+        root(),
+        WalletHarnessProps {
+            wallet,
+            latest: latest.clone(),
+        },
+    )
+    .render();
+    settle().await;
+    (latest, app)
+}
+
+/// a fresh element to mount an app on
+pub fn root() -> web_sys::Element {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let root = document.create_element("div").unwrap();
+    document.body().unwrap().append_child(&root).unwrap();
+    root
+}
+
+/// let pending renders and effects run
+pub async fn settle() {
+    sleep(Duration::from_millis(20)).await;
+}
@@ -0,0 +1,60 @@
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{Object, Reflect};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+use yew_ethereum_provider::js_error_to_string;
+
+fn object(properties: &[(&str, JsValue)]) -> JsValue {
+    let object = Object::new();
+    for (name, value) in properties {
+        Reflect::set(&object, &JsValue::from_str(name), value).unwrap();
+    }
+    object.into()
+}
+
+#[wasm_bindgen_test]
+fn string_errors_are_kept() {
+    assert_eq!(
+        js_error_to_string(&JsValue::from_str("User denied account authorization")),
+        "User denied account authorization"
+    );
+}
+
+#[wasm_bindgen_test]
+fn error_objects_give_their_message() {
+    let error: JsValue = js_sys::Error::new("execution reverted").into();
+    assert_eq!(js_error_to_string(&error), "execution reverted");
+
+    Reflect::set(
+        &error,
+        &JsValue::from_str("code"),
+        &JsValue::from_str("CALL_EXCEPTION"),
+    )
+    .unwrap();
+    assert_eq!(
+        js_error_to_string(&error),
+        "execution reverted (code CALL_EXCEPTION)"
+    );
+}
+
+#[wasm_bindgen_test]
+fn plain_objects_give_their_message_and_code() {
+    let error = object(&[
+        ("code", JsValue::from_f64(4001.0)),
+        ("message", JsValue::from_str("User rejected the request.")),
+    ]);
+    assert_eq!(
+        js_error_to_string(&error),
+        "User rejected the request. (code 4001)"
+    );
+
+    let error = object(&[("message", JsValue::from_str("Internal JSON-RPC error."))]);
+    assert_eq!(js_error_to_string(&error), "Internal JSON-RPC error.");
+}
+
+#[wasm_bindgen_test]
+fn other_objects_are_serialized() {
+    let error = object(&[("reason", JsValue::from_str("unknown"))]);
+    assert_eq!(js_error_to_string(&error), r#"{"reason":"unknown"}"#);
+}
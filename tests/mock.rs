@@ -0,0 +1,447 @@
+#![cfg(target_arch = "wasm32")]
+
+mod common;
+
+use common::{render, root, settle, Latest};
+use serde_json::json;
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web3::{
+    futures::future::join,
+    types::{H160, U256},
+};
+use web_sys::HtmlElement;
+use yew::{platform::time::sleep, prelude::*};
+use yew_ethereum_provider::{
+    ens, use_balance, use_mock_ethereum, BalanceCacheProvider, ConnectButton, Erc20DecimalsCache,
+    EthereumError, MockProvider, UseBalanceHandle, UseEthereumHandle,
+};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn starts_connected_with_the_preset_accounts() {
+    let account = H160::repeat_byte(0x11);
+    let (latest, _app) = render(MockProvider::new(vec![account], 137)).await;
+    let ethereum = latest.get();
+    assert!(ethereum.connected());
+    assert_eq!(ethereum.address(), Some(&account));
+    assert_eq!(ethereum.chain_id(), Some(137));
+}
+
+#[wasm_bindgen_test]
+async fn connect_adopts_the_mocked_accounts() {
+    let account = H160::repeat_byte(0x22);
+    let mock = MockProvider::new(vec![], 1).respond("eth_requestAccounts", json!([account]));
+    let (latest, _app) = render(mock).await;
+    assert!(!latest.get().connected());
+
+    latest.get().connect().await.unwrap();
+    settle().await;
+    let ethereum = latest.get();
+    assert!(ethereum.connected());
+    assert_eq!(ethereum.address(), Some(&account));
+    assert_eq!(ethereum.chain_id(), Some(1));
+}
+
+#[wasm_bindgen_test]
+async fn unmocked_methods_have_no_provider() {
+    let (latest, _app) = render(MockProvider::new(vec![], 1)).await;
+    let result = latest.get().request("eth_gasPrice", vec![]).await;
+    assert!(matches!(result, Err(EthereumError::NoProvider)));
+}
+
+#[wasm_bindgen_test]
+async fn middleware_can_clear_middleware() {
+    let (latest, _app) = render(MockProvider::new(vec![], 1)).await;
+    let ethereum = latest.get();
+    {
+        let handle = ethereum.clone();
+        ethereum.add_request_middleware(move |_, _| {
+            handle.clear_middleware();
+            Some(json!("0x1"))
+        });
+    }
+
+    let first = ethereum.request("eth_gasPrice", vec![]).await;
+    assert_eq!(first.unwrap(), json!("0x1"));
+    let second = ethereum.request("eth_gasPrice", vec![]).await;
+    assert!(matches!(second, Err(EthereumError::NoProvider)));
+}
+
+#[wasm_bindgen_test]
+async fn concurrent_connects_share_one_request() {
+    let account = H160::repeat_byte(0x33);
+    let mock = MockProvider::new(vec![], 1)
+        .respond("eth_requestAccounts", json!([account]))
+        .latency(Duration::from_millis(50));
+    let (latest, _app) = render(mock).await;
+    let ethereum = latest.get();
+    let requests = Rc::new(Cell::new(0));
+    {
+        let requests = requests.clone();
+        ethereum.add_request_middleware(move |method, _| {
+            if method == "eth_requestAccounts" {
+                requests.set(requests.get() + 1);
+            }
+            None
+        });
+    }
+
+    let (first, second) = join(ethereum.connect(), ethereum.connect()).await;
+    assert!(first.is_ok());
+    assert!(second.is_ok());
+    assert_eq!(requests.get(), 1);
+
+    // the finished flow is cleared, so connecting again asks the wallet again
+    ethereum.connect().await.unwrap();
+    assert_eq!(requests.get(), 2);
+}
+
+#[derive(Properties, PartialEq)]
+struct ConnectButtonProps {
+    mock: MockProvider,
+    latest: Latest,
+    connects: Rc<Cell<u32>>,
+}
+
+#[function_component]
+fn ConnectButtonHarness(props: &ConnectButtonProps) -> Html {
+    let ethereum = use_mock_ethereum(props.mock.clone());
+    props.latest.set(ethereum.clone());
+    let on_connect = {
+        let connects = props.connects.clone();
+        Callback::from(move |_| connects.set(connects.get() + 1))
+    };
+    html! {
+        <ContextProvider<Option<UseEthereumHandle>> context={Some(ethereum)}>
+            <ConnectButton {on_connect} />
+        </ContextProvider<Option<UseEthereumHandle>>>
+    }
+}
+
+#[wasm_bindgen_test]
+async fn on_connect_fires_only_for_the_buttons_own_connection() {
+    let mock = MockProvider::new(vec![], 1)
+        .respond("eth_requestAccounts", json!([H160::repeat_byte(0x77)]));
+    let latest = Latest::default();
+    let connects = Rc::new(Cell::new(0));
+    let root = root();
+    let _app = yew::Renderer::<ConnectButtonHarness>::with_root_and_props(
+        root.clone(),
+        ConnectButtonProps {
+            mock,
+            latest: latest.clone(),
+            connects: connects.clone(),
+        },
+    )
+    .render();
+    settle().await;
+
+    // connected by someone else
+    latest.get().connect().await.unwrap();
+    settle().await;
+    assert_eq!(connects.get(), 0);
+
+    latest.get().disconnect();
+    settle().await;
+    let button = root.query_selector("button").unwrap().unwrap();
+    button.unchecked_into::<HtmlElement>().click();
+    settle().await;
+    assert!(latest.get().connected());
+    assert_eq!(connects.get(), 1);
+}
+
+#[wasm_bindgen_test]
+async fn sign_typed_data_sends_the_address_first() {
+    let account = H160::repeat_byte(0x44);
+    let (latest, _app) = render(MockProvider::new(vec![account], 1)).await;
+    let sent = Rc::new(RefCell::new(None));
+    {
+        let sent = sent.clone();
+        latest.get().add_request_middleware(move |method, params| {
+            if method != "eth_signTypedData_v4" {
+                return None;
+            }
+            *sent.borrow_mut() = Some(params.to_vec());
+            Some(json!("0x1234"))
+        });
+    }
+
+    let typed_data = json!({
+        "types": { "EIP712Domain": [] },
+        "primaryType": "EIP712Domain",
+        "domain": {},
+        "message": {},
+    });
+    let signature = latest.get().sign_typed_data(typed_data.clone()).await;
+    assert_eq!(signature.unwrap(), "0x1234");
+    assert_eq!(
+        *sent.borrow(),
+        Some(vec![json!(account), json!(typed_data.to_string())])
+    );
+}
+
+#[wasm_bindgen_test]
+async fn sign_typed_data_requires_domain_types_and_message() {
+    let (latest, _app) = render(MockProvider::new(vec![H160::repeat_byte(0x44)], 1)).await;
+    let result = latest
+        .get()
+        .sign_typed_data(json!({ "types": {}, "message": {} }))
+        .await;
+    assert!(matches!(
+        result,
+        Err(EthereumError::InvalidRequest(message)) if message.contains("domain")
+    ));
+}
+
+#[wasm_bindgen_test]
+async fn personal_sign_sends_the_hex_message_first() {
+    let account = H160::repeat_byte(0x55);
+    let (latest, _app) = render(MockProvider::new(vec![account], 1)).await;
+    let sent = Rc::new(RefCell::new(None));
+    {
+        let sent = sent.clone();
+        latest.get().add_request_middleware(move |method, params| {
+            if method != "personal_sign" {
+                return None;
+            }
+            *sent.borrow_mut() = Some(params.to_vec());
+            Some(json!("0x1234"))
+        });
+    }
+
+    let signature = latest.get().personal_sign("héllo").await;
+    assert_eq!(signature.unwrap(), "0x1234");
+    // MetaMask decodes the hex of the utf-8 bytes back into the text it shows
+    assert_eq!(
+        *sent.borrow(),
+        Some(vec![json!("0x68c3a96c6c6f"), json!(account)])
+    );
+}
+
+#[derive(Properties, PartialEq)]
+struct BalanceProps {
+    mock: MockProvider,
+    latest: Latest<UseBalanceHandle>,
+    reads: Rc<Cell<u32>>,
+}
+
+#[function_component]
+fn BalanceHarness(props: &BalanceProps) -> Html {
+    let ethereum = use_mock_ethereum(props.mock.clone());
+    use_balance_read_count(&ethereum, &props.reads);
+    props.latest.set(use_balance(Some(ethereum)));
+    html! {}
+}
+
+/// count the `eth_getBalance` requests of `ethereum`, from its first render on
+#[hook]
+fn use_balance_read_count(ethereum: &UseEthereumHandle, reads: &Rc<Cell<u32>>) {
+    let ethereum = ethereum.clone();
+    let reads = reads.clone();
+    use_memo(
+        move |_| {
+            ethereum.add_request_middleware(move |method, _| {
+                if method == "eth_getBalance" {
+                    reads.set(reads.get() + 1);
+                }
+                None
+            })
+        },
+        (),
+    );
+}
+
+#[wasm_bindgen_test]
+async fn refetching_keeps_the_balance_until_read_again() {
+    let mock = MockProvider::new(vec![H160::repeat_byte(0x55)], 1)
+        .respond("eth_getBalance", json!("0x64"))
+        .latency(Duration::from_millis(100));
+    let latest = Latest::default();
+    let reads = Rc::new(Cell::new(0));
+    let _app = yew::Renderer::<BalanceHarness>::with_root_and_props(
+        root(),
+        BalanceProps {
+            mock,
+            latest: latest.clone(),
+            reads: reads.clone(),
+        },
+    )
+    .render();
+    sleep(Duration::from_millis(150)).await;
+    assert_eq!(latest.get().balance, Some(U256::from(100)));
+    assert_eq!(reads.get(), 1);
+
+    latest.get().refetch.emit(());
+    settle().await;
+    // still reading, the previous balance stays
+    assert_eq!(latest.get().balance, Some(U256::from(100)));
+    sleep(Duration::from_millis(150)).await;
+    assert_eq!(latest.get().balance, Some(U256::from(100)));
+    assert_eq!(reads.get(), 2);
+}
+
+#[derive(Properties, PartialEq)]
+struct SharedBalanceProps {
+    mock: MockProvider,
+    consumers: Vec<Latest<UseBalanceHandle>>,
+    reads: Rc<Cell<u32>>,
+}
+
+#[function_component]
+fn SharedBalanceHarness(props: &SharedBalanceProps) -> Html {
+    let ethereum = use_mock_ethereum(props.mock.clone());
+    use_balance_read_count(&ethereum, &props.reads);
+    html! {
+        <ContextProvider<Option<UseEthereumHandle>> context={Some(ethereum)}>
+            <BalanceCacheProvider>
+                { for props.consumers.iter().map(|latest| html! {
+                    <BalanceConsumer latest={latest.clone()} />
+                }) }
+            </BalanceCacheProvider>
+        </ContextProvider<Option<UseEthereumHandle>>>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct BalanceConsumerProps {
+    latest: Latest<UseBalanceHandle>,
+}
+
+#[function_component]
+fn BalanceConsumer(props: &BalanceConsumerProps) -> Html {
+    let ethereum = use_context::<Option<UseEthereumHandle>>().flatten();
+    props.latest.set(use_balance(ethereum));
+    html! {}
+}
+
+#[wasm_bindgen_test]
+async fn one_balance_read_serves_every_consumer() {
+    let mock = MockProvider::new(vec![H160::repeat_byte(0x66)], 1)
+        .respond("eth_getBalance", json!("0x64"))
+        .latency(Duration::from_millis(50));
+    let consumers = vec![Latest::default(), Latest::default(), Latest::default()];
+    let reads = Rc::new(Cell::new(0));
+    let _app = yew::Renderer::<SharedBalanceHarness>::with_root_and_props(
+        root(),
+        SharedBalanceProps {
+            mock,
+            consumers: consumers.clone(),
+            reads: reads.clone(),
+        },
+    )
+    .render();
+    sleep(Duration::from_millis(150)).await;
+
+    assert_eq!(reads.get(), 1);
+    for consumer in &consumers {
+        assert_eq!(consumer.get().balance, Some(U256::from(100)));
+    }
+}
+
+#[wasm_bindgen_test]
+async fn second_decimals_query_hits_the_cache() {
+    let mock = MockProvider::new(vec![H160::repeat_byte(0x77)], 1)
+        .respond("eth_call", json!(format!("0x{:064x}", 6)));
+    let (latest, _app) = render(mock).await;
+    let ethereum = latest.get();
+    let calls = Rc::new(Cell::new(0));
+    {
+        let calls = calls.clone();
+        ethereum.add_request_middleware(move |method, _| {
+            if method == "eth_call" {
+                calls.set(calls.get() + 1);
+            }
+            None
+        });
+    }
+
+    let cache = Erc20DecimalsCache::default();
+    let token = H160::repeat_byte(0x88);
+    assert_eq!(cache.decimals(&ethereum, token).await.unwrap(), 6);
+    assert_eq!(cache.decimals(&ethereum, token).await.unwrap(), 6);
+    assert_eq!(calls.get(), 1);
+    assert_eq!(cache.get(1, token), Some(6));
+}
+
+/// answer `eth_call`s to the ENS registry with `resolver` and to `resolver` with `owner`,
+/// counting them
+fn serve_ens(ethereum: &UseEthereumHandle, resolver: H160, owner: H160) -> Rc<Cell<u32>> {
+    let calls = Rc::new(Cell::new(0));
+    let counted = calls.clone();
+    ethereum.add_request_middleware(move |method, params| {
+        if method != "eth_call" {
+            return None;
+        }
+        counted.set(counted.get() + 1);
+        let to: H160 = serde_json::from_value(params.first()?["to"].clone()).ok()?;
+        let answer = if to == ens::registry() {
+            resolver
+        } else if to == resolver {
+            owner
+        } else {
+            return None;
+        };
+        Some(json!(format!("0x{:0>64}", format!("{:x}", answer))))
+    });
+    calls
+}
+
+#[wasm_bindgen_test]
+async fn ens_resolves_through_a_mainnet_wallet() {
+    let (latest, _app) = render(MockProvider::new(vec![H160::repeat_byte(0x99)], 1)).await;
+    let ethereum = latest.get();
+    let owner = H160::repeat_byte(0xd8);
+    let calls = serve_ens(&ethereum, H160::repeat_byte(0x42), owner);
+
+    assert_eq!(
+        ethereum.resolve_ens("vitalik.eth").await.unwrap(),
+        Some(owner)
+    );
+    assert_eq!(calls.get(), 2);
+    // resolved names are cached
+    assert_eq!(
+        ethereum.resolve_ens("Vitalik.eth").await.unwrap(),
+        Some(owner)
+    );
+    assert_eq!(calls.get(), 2);
+}
+
+#[wasm_bindgen_test]
+async fn ens_fails_fast_off_mainnet() {
+    let (latest, _app) = render(MockProvider::new(vec![H160::repeat_byte(0x99)], 137)).await;
+    let ethereum = latest.get();
+    let calls = serve_ens(&ethereum, H160::repeat_byte(0x42), H160::repeat_byte(0xd8));
+
+    let result = ethereum.resolve_ens("vitalik.eth").await;
+    assert!(matches!(
+        result,
+        Err(EthereumError::WrongNetwork {
+            expected: 1,
+            actual: Some(137)
+        })
+    ));
+    assert_eq!(calls.get(), 0);
+}
+
+#[wasm_bindgen_test]
+async fn ens_uses_the_mainnet_rpc_off_mainnet() {
+    let (latest, _app) = render(MockProvider::new(vec![H160::repeat_byte(0x99)], 137)).await;
+    let ethereum = latest.get();
+    let calls = serve_ens(&ethereum, H160::repeat_byte(0x42), H160::repeat_byte(0xd8));
+    // nothing listens there, the point is that the endpoint is tried rather than the wallet
+    ethereum
+        .set_ens_rpc_url(Some("http://127.0.0.1:9"))
+        .unwrap();
+
+    let result = ethereum.resolve_ens("vitalik.eth").await;
+    assert!(result.is_err());
+    assert!(!matches!(result, Err(EthereumError::WrongNetwork { .. })));
+    assert_eq!(calls.get(), 0);
+}
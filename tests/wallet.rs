@@ -0,0 +1,211 @@
+#![cfg(target_arch = "wasm32")]
+
+mod common;
+
+use common::{addresses, render_wallet, settle, FakeWallet};
+use js_sys::Function;
+use std::{cell::Cell, rc::Rc, time::Duration};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_test::*;
+use web3::types::H160;
+use yew::platform::time::sleep;
+use yew_ethereum_provider::{chain, AddChainOutcome, EthereumError, DEFAULT_EVENT_DEBOUNCE_MS};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// a javascript function counting its calls
+fn counting_listener() -> (Rc<Cell<u32>>, Closure<dyn FnMut(JsValue)>) {
+    let calls = Rc::new(Cell::new(0));
+    let listener = {
+        let calls = calls.clone();
+        Closure::<dyn FnMut(JsValue)>::new(move |_| calls.set(calls.get() + 1))
+    };
+    (calls, listener)
+}
+
+#[wasm_bindgen_test]
+async fn connecting_twice_does_not_double_fire_accounts_changed() {
+    let wallet = FakeWallet::new(&[H160::repeat_byte(0x11)], 1);
+    let (latest, _app) = render_wallet(wallet.clone()).await;
+    let ethereum = latest.get();
+    ethereum.set_event_debounce(0);
+    ethereum.connect().await.unwrap();
+    ethereum.connect().await.unwrap();
+    settle().await;
+
+    let (fired, listener) = counting_listener();
+    ethereum.set_js_on_accounts_changed(listener.as_ref().unchecked_ref::<Function>().clone());
+    let switched = H160::repeat_byte(0x22);
+    wallet.emit("accountsChanged", &addresses(&[switched]));
+    settle().await;
+
+    assert_eq!(fired.get(), 1);
+    assert_eq!(latest.get().address(), Some(&switched));
+}
+
+#[wasm_bindgen_test]
+async fn remounting_does_not_accumulate_subscriptions_or_listeners() {
+    const EVENTS: [&str; 5] = [
+        "accountsChanged",
+        "chainChanged",
+        "connect",
+        "disconnect",
+        "message",
+    ];
+    let wallet = FakeWallet::new(&[H160::repeat_byte(0x11)], 1);
+    let mut listeners = None;
+    for mounts in 1..=3 {
+        let (latest, app) = render_wallet(wallet.clone()).await;
+        latest.get().connect().await.unwrap();
+        settle().await;
+        drop(latest);
+        app.destroy();
+        settle().await;
+
+        assert_eq!(wallet.requests("eth_subscribe"), mounts);
+        assert_eq!(wallet.requests("eth_unsubscribe"), mounts);
+        let left = EVENTS.map(|event| wallet.listeners(event));
+        assert_eq!(*listeners.get_or_insert(left), left);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn add_chain_the_wallet_is_on_already_exists() {
+    let wallet = FakeWallet::new(&[H160::repeat_byte(0x11)], 43113);
+    let (latest, _app) = render_wallet(wallet.clone()).await;
+
+    let outcome = latest.get().add_chain(&chain::avalanche_testnet()).await;
+    assert_eq!(outcome.unwrap(), AddChainOutcome::AlreadyExists);
+    assert_eq!(wallet.requests("wallet_addEthereumChain"), 0);
+}
+
+#[wasm_bindgen_test]
+async fn add_chain_the_wallet_refuses_as_known_already_exists() {
+    let wallet = FakeWallet::new(&[H160::repeat_byte(0x11)], 1);
+    wallet.reject(
+        "wallet_addEthereumChain",
+        -32602,
+        "May not specify default MetaMask chain.",
+    );
+    let (latest, _app) = render_wallet(wallet.clone()).await;
+
+    let outcome = latest.get().add_chain(&chain::avalanche_testnet()).await;
+    assert_eq!(outcome.unwrap(), AddChainOutcome::AlreadyExists);
+    assert_eq!(wallet.requests("wallet_addEthereumChain"), 1);
+}
+
+#[wasm_bindgen_test]
+async fn add_chain_declined_by_the_user_is_rejected() {
+    let wallet = FakeWallet::new(&[H160::repeat_byte(0x11)], 1);
+    wallet.reject(
+        "wallet_addEthereumChain",
+        4001,
+        "User rejected the request.",
+    );
+    let (latest, _app) = render_wallet(wallet).await;
+
+    let outcome = latest.get().add_chain(&chain::avalanche_testnet()).await;
+    assert_eq!(outcome.unwrap(), AddChainOutcome::Rejected);
+}
+
+#[wasm_bindgen_test]
+async fn add_chain_accepted_is_added() {
+    let wallet = FakeWallet::new(&[H160::repeat_byte(0x11)], 1);
+    wallet.respond("wallet_addEthereumChain", &JsValue::NULL);
+    let (latest, _app) = render_wallet(wallet).await;
+
+    let outcome = latest.get().add_chain(&chain::avalanche_testnet()).await;
+    assert_eq!(outcome.unwrap(), AddChainOutcome::Added);
+}
+
+#[wasm_bindgen_test]
+async fn a_burst_of_events_coalesces_into_one_update() {
+    let wallet = FakeWallet::new(&[H160::repeat_byte(0x11)], 1);
+    let (latest, _app) = render_wallet(wallet.clone()).await;
+    let ethereum = latest.get();
+    ethereum.connect().await.unwrap();
+    settle().await;
+
+    let (accounts_changed, accounts_listener) = counting_listener();
+    let (chain_changed, chain_listener) = counting_listener();
+    ethereum.set_js_on_accounts_changed(
+        accounts_listener
+            .as_ref()
+            .unchecked_ref::<Function>()
+            .clone(),
+    );
+    ethereum.set_js_on_chain_changed(chain_listener.as_ref().unchecked_ref::<Function>().clone());
+    for byte in 0x20..0x25 {
+        wallet.emit("accountsChanged", &addresses(&[H160::repeat_byte(byte)]));
+    }
+    for chain_id in ["0x89", "0xa", "0x2105"] {
+        wallet.emit("chainChanged", &JsValue::from_str(chain_id));
+    }
+    sleep(Duration::from_millis(DEFAULT_EVENT_DEBOUNCE_MS as u64 + 50)).await;
+
+    assert_eq!(accounts_changed.get(), 1);
+    assert_eq!(chain_changed.get(), 1);
+    let ethereum = latest.get();
+    assert_eq!(ethereum.address(), Some(&H160::repeat_byte(0x24)));
+    assert_eq!(ethereum.chain_id(), Some(0x2105));
+}
+
+#[wasm_bindgen_test]
+async fn connect_to_declined_is_rejected_and_reported() {
+    let wallet = FakeWallet::new(&[H160::repeat_byte(0x11)], 1);
+    wallet.reject("eth_requestAccounts", 4001, "User rejected the request.");
+    let (latest, _app) = render_wallet(wallet).await;
+
+    let result = latest.get().connect_to(&chain::avalanche_testnet()).await;
+    assert!(matches!(result, Err(EthereumError::ConnectRejected)));
+    settle().await;
+    let ethereum = latest.get();
+    assert!(!ethereum.connected());
+    assert!(matches!(
+        ethereum.last_error(),
+        Some(EthereumError::UserRejected)
+    ));
+}
+
+#[wasm_bindgen_test]
+async fn wallet_info_uses_the_eip6963_announcement() {
+    let wallet = FakeWallet::new(&[H160::repeat_byte(0x11)], 1);
+    // answers requests for as long as the test page lives, but only ever with this wallet
+    Function::new_with_args(
+        "provider",
+        "window.addEventListener('eip6963:requestProvider', () => {
+            const info = {
+                uuid: '350670db-19fa-4704-a166-e52e178b59d2',
+                name: 'Fake Wallet',
+                icon: 'data:image/svg+xml,<svg/>',
+                rdns: 'org.example.fake',
+            };
+            const detail = Object.freeze({ info, provider });
+            window.dispatchEvent(new CustomEvent('eip6963:announceProvider', { detail }));
+        });",
+    )
+    .call1(&JsValue::NULL, &wallet.provider())
+    .unwrap();
+    let (latest, _app) = render_wallet(wallet).await;
+
+    let info = latest.get().wallet_info().unwrap();
+    assert_eq!(info.name, "Fake Wallet");
+    assert_eq!(info.rdns.as_deref(), Some("org.example.fake"));
+    assert_eq!(info.icon.as_deref(), Some("data:image/svg+xml,<svg/>"));
+}
+
+#[wasm_bindgen_test]
+async fn wallet_info_falls_back_to_the_provider_flags() {
+    let wallet = FakeWallet::new(&[H160::repeat_byte(0x11)], 1);
+    js_sys::Reflect::set(
+        &wallet.provider(),
+        &JsValue::from_str("isMetaMask"),
+        &JsValue::TRUE,
+    )
+    .unwrap();
+    let (latest, _app) = render_wallet(wallet).await;
+
+    let info = latest.get().wallet_info().unwrap();
+    assert_eq!(info.name, "MetaMask");
+    assert_eq!(info.icon, None);
+}